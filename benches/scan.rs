@@ -0,0 +1,46 @@
+//! Scan throughput on inputs of different sizes.
+//!
+//! ```text
+//! cargo bench --bench scan
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use wasm2env::{scan_wasm_bytes, scan_wasm_bytes_detailed};
+
+/// `(name, bytes)` from smallest to largest: a hand-written core module from
+/// `tests/fixtures`, then the two components checked in at the repository
+/// root, `openai_component.wasm` (~540 KiB) and `mcp_component.wasm`
+/// (~3.7 MiB). Paths are relative to the crate root, where `cargo bench` runs.
+fn inputs() -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        (
+            "fat-pointer-load",
+            wat::parse_file("tests/fixtures/fat-pointer-load.wat").unwrap(),
+        ),
+        (
+            "openai_component",
+            std::fs::read("openai_component.wasm").unwrap(),
+        ),
+        (
+            "mcp_component",
+            std::fs::read("mcp_component.wasm").unwrap(),
+        ),
+    ]
+}
+
+fn scan(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scan");
+    for (name, bytes) in inputs() {
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::new("names", name), &bytes, |b, bytes| {
+            b.iter(|| scan_wasm_bytes(bytes).unwrap());
+        });
+        group.bench_with_input(BenchmarkId::new("detailed", name), &bytes, |b, bytes| {
+            b.iter(|| scan_wasm_bytes_detailed(bytes).unwrap());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scan);
+criterion_main!(benches);
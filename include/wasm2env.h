@@ -0,0 +1,32 @@
+/* C interface to wasm2env. See src/ffi.rs for the full contract. */
+
+#ifndef WASM2ENV_H
+#define WASM2ENV_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Scan the `len` bytes at `ptr` (a core module or component).
+ *
+ * Returns 0 on success, with the sorted variable names joined by '\n' in a
+ * NUL-terminated buffer at `*out` and its length (without the NUL) in
+ * `*out_len`. Returns 1 for a null argument and 2 if the bytes couldn't be
+ * scanned; `*out` is then NULL.
+ *
+ * The caller owns `*out` and must release it with wasm2env_free().
+ */
+int32_t wasm2env_scan(const uint8_t *ptr, size_t len, char **out, size_t *out_len);
+
+/* Release a buffer returned by wasm2env_scan(). NULL is ignored. */
+void wasm2env_free(char *buf);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* WASM2ENV_H */
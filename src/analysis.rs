@@ -6,58 +6,639 @@
 //! 3. Walk all functions, simulating the stack at env-related call sites
 //! 4. Extract string arguments that look like env var names
 
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use gimli::{EndianSlice, LittleEndian, Reader, Section};
 use walrus::ir::{Instr, InstrSeqId};
-use walrus::{FunctionId, ImportKind};
+use walrus::{ExportItem, FunctionId, ImportKind};
 
-use crate::extract::extract_core_modules;
-use crate::stack::{walk_seq, StackState};
-use crate::strings::{build_memory_map, collect_globals};
-use anyhow::Result;
+use crate::config::{CalleeKind, ScanConfig};
+use crate::const_fold::fold_extended_consts;
+use crate::error::Result;
+use crate::exceptions::lower_exceptions;
+use crate::extract::{extract_core_modules, CoreModules};
+use crate::report::{
+    canonicalize_case, flag_corroborated, retain_min_functions, EnvVarHit, HitSource, ScanReport,
+    ScanStats, SkippedFunction,
+};
+use crate::score::classify_env_var;
+use crate::stack::{walk_seq, CallSites, EnvCall, StackState, WalkContext};
+use crate::strings::{
+    apply_memory_writes, build_memory_map, collect_globals, is_memory64, untraced_name_rule,
+    MemoryMap,
+};
+use crate::stub::{retype_unsupported_locals, stub_unsupported_functions};
 
 /// Main detection function — call-graph-based, not heuristic.
-pub fn detect_env_vars(wasm_bytes: &[u8]) -> Result<HashSet<String>> {
-    let mut env_vars = HashSet::new();
+///
+/// Reports one hit per distinct (module, function, ptr, len) call-site read,
+/// ordered by module, function, pointer and name.
+pub fn detect_env_vars(wasm_bytes: &[u8], config: &ScanConfig) -> Result<ScanReport> {
+    Ok(detect_in_core_modules(
+        &extract_core_modules(wasm_bytes)?,
+        config,
+    ))
+}
+
+/// [`detect_env_vars`] on core modules that were already extracted, e.g.
+/// while streaming the binary in.
+pub fn detect_in_core_modules(core_modules: &CoreModules, config: &ScanConfig) -> ScanReport {
+    let mut hits = Vec::new();
+    let mut stats = ScanStats::default();
+    scan_core_modules(core_modules, config, &mut stats, &mut |hit| hits.push(hit));
+    sort_hits(&mut hits);
+    filter_hits(&mut hits, config);
+    dedup_rejections(&mut stats);
+
+    ScanReport {
+        hits,
+        truncated_at: core_modules.truncated_at,
+        stats,
+    }
+}
+
+/// Detection on a module the caller already parsed, reported as module 0.
+/// Same ordering as [`detect_env_vars`].
+pub fn detect_in_module(module: &walrus::Module, config: &ScanConfig) -> ScanReport {
+    let mut hits = Vec::new();
+    let mut stats = ScanStats::default();
+    // No binary to point into
+    scan_module(module, 0, &[], config, &mut stats, &mut |hit| {
+        hits.push(hit);
+    });
+    sort_hits(&mut hits);
+    filter_hits(&mut hits, config);
+    dedup_rejections(&mut stats);
+
+    ScanReport {
+        hits,
+        truncated_at: None,
+        stats,
+    }
+}
+
+/// Order hits by module, function, pointer and name, dropping exact
+/// duplicates. Remaining ties are broken on the whole hit, so the order never
+/// depends on how the hits were collected.
+fn sort_hits(hits: &mut Vec<EnvVarHit>) {
+    hits.sort_by(|a, b| {
+        (a.module_index, a.function_index, a.ptr, &a.name)
+            .cmp(&(b.module_index, b.function_index, b.ptr, &b.name))
+            .then_with(|| a.cmp(b))
+    });
+    hits.dedup();
+}
+
+/// Flag corroborated hits, then apply the config's filters.
+fn filter_hits(hits: &mut Vec<EnvVarHit>, config: &ScanConfig) {
+    if config.dedup_case_insensitive {
+        canonicalize_case(hits);
+        // Renamed hits can now be out of order or duplicates
+        sort_hits(hits);
+    }
+    flag_corroborated(hits);
+    if config.corroborated_only {
+        hits.retain(|hit| hit.corroborated);
+    }
+    hits.retain(|hit| hit.confidence >= config.min_confidence);
+    retain_min_functions(hits, config.min_functions);
+}
+
+/// The same string is usually rejected at many call sites; keep one entry.
+fn dedup_rejections(stats: &mut ScanStats) {
+    stats.rejected_candidates.sort();
+    stats.rejected_candidates.dedup();
+}
 
+/// Run detection, passing every hit to `on_hit` as soon as the function (or
+/// export) it was found in has been analyzed. Hits are neither sorted nor
+/// de-duplicated. Returns the truncation offset, if the input was truncated.
+pub fn for_each_hit(
+    wasm_bytes: &[u8],
+    config: &ScanConfig,
+    stats: &mut ScanStats,
+    on_hit: &mut dyn FnMut(EnvVarHit),
+) -> Result<Option<usize>> {
     let core_modules = extract_core_modules(wasm_bytes)?;
+    scan_core_modules(&core_modules, config, stats, on_hit);
+    Ok(core_modules.truncated_at)
+}
 
-    for module_bytes in &core_modules {
-        if let Ok(module) = walrus::Module::from_buffer(module_bytes) {
-            analyze_module(&module, &mut env_vars);
+/// Every string passed to any call as a (ptr, len) pair, across all core
+/// modules, sorted and de-duplicated. Uses the same stack simulation as
+/// detection but skips env var name validation.
+pub fn collect_call_strings(wasm_bytes: &[u8], config: &ScanConfig) -> Result<Vec<String>> {
+    let core_modules = extract_core_modules(wasm_bytes)?;
+    let mut stats = ScanStats::default();
+    let mut strings = Vec::new();
+    for (module_index, core_module) in core_modules.modules.iter().enumerate() {
+        let module_bytes = fold_extended_consts(&core_module.bytes);
+        if let Some(module) = load_module(&module_bytes, module_index, &mut stats) {
+            strings.extend(call_strings(&module, config));
         }
     }
+    strings.sort();
+    strings.dedup();
+    Ok(strings)
+}
 
-    Ok(env_vars)
+/// [`collect_call_strings`] for one loaded module.
+fn call_strings(module: &walrus::Module, config: &ScanConfig) -> Vec<String> {
+    let mut memory_map = build_memory_map(module);
+    let global_values = collect_globals(module);
+    replay_memory_writes(module, config, &mut memory_map, &global_values);
+    let chains = EnvCallChains::default();
+
+    let mut strings = Vec::new();
+    for (_, local_func) in module.funcs.iter_local() {
+        let ctx = WalkContext {
+            module,
+            func: local_func,
+            memory_map: &memory_map,
+            chains: &chains,
+            config,
+            memory64: is_memory64(module),
+            log_sink: None,
+            all_strings: true,
+        };
+        let mut found = CallSites::default();
+        walk_seq(
+            &ctx,
+            local_func.entry_block(),
+            &mut StackState::new(),
+            &mut global_values.clone(),
+            &mut found,
+        );
+        strings.extend(found.strings);
+    }
+    strings
+}
+
+/// Load and scan each core module in turn.
+fn scan_core_modules(
+    core_modules: &CoreModules,
+    config: &ScanConfig,
+    stats: &mut ScanStats,
+    on_hit: &mut dyn FnMut(EnvVarHit),
+) {
+    stats
+        .environment_imports
+        .clone_from(&core_modules.environment_imports);
+    for (module_index, core_module) in core_modules.modules.iter().enumerate() {
+        let module_bytes = fold_extended_consts(&core_module.bytes);
+        let Some(module) = load_module(&module_bytes, module_index, stats) else {
+            continue;
+        };
+        let data_offsets = &core_module.data_offsets;
+        scan_module(&module, module_index, data_offsets, config, stats, on_hit);
+    }
+}
+
+/// Run every enabled pass over one loaded core module. `data_offsets` are
+/// its data segments' offsets in the scanned file, if it came from one.
+fn scan_module(
+    module: &walrus::Module,
+    module_index: usize,
+    data_offsets: &[usize],
+    config: &ScanConfig,
+    stats: &mut ScanStats,
+    on_hit: &mut dyn FnMut(EnvVarHit),
+) {
+    if reads_whole_environment(module) {
+        stats.whole_environment_modules.push(module_index);
+    }
+    // Object files from before `wasm-ld` have placeholder addresses that only
+    // relocations give meaning to, so pointers can't be traced
+    if is_relocatable(module) {
+        stats.relocatable_modules.push(module_index);
+        scan_data_strings(module, module_index, data_offsets, config, on_hit);
+    } else {
+        analyze_module(module, module_index, data_offsets, config, stats, on_hit);
+    }
+    if config.scan_exports {
+        scan_export_names(module, module_index, config, on_hit);
+    }
+    if config.debug_info {
+        scan_debug_strings(module, module_index, config, on_hit);
+    }
+}
+
+/// Whether a module is a relocatable object file (has a `linking` section).
+fn is_relocatable(module: &walrus::Module) -> bool {
+    module
+        .customs
+        .iter()
+        .any(|(_, section)| section.name() == "linking")
+}
+
+/// Parse a core module with walrus. If walrus rejects it, retry with
+/// exception handling lowered to plain blocks, then with unsupported local
+/// types retyped, and then with the function bodies it still can't handle
+/// stubbed out, recording them in `stats`.
+pub fn load_module(
+    module_bytes: &[u8],
+    module_index: usize,
+    stats: &mut ScanStats,
+) -> Option<walrus::Module> {
+    if let Ok(module) = walrus::Module::from_buffer(module_bytes) {
+        return Some(module);
+    }
+
+    let module_bytes = lower_exceptions(module_bytes);
+    if let Ok(module) = walrus::Module::from_buffer(&module_bytes) {
+        return Some(module);
+    }
+
+    let module_bytes = retype_unsupported_locals(&module_bytes);
+    if let Ok(module) = walrus::Module::from_buffer(&module_bytes) {
+        return Some(module);
+    }
+
+    let stubbed = stub_unsupported_functions(&module_bytes)?;
+    let module = walrus::Module::from_buffer(&stubbed.bytes).ok()?;
+    stats
+        .skipped_functions
+        .extend(
+            stubbed
+                .skipped
+                .into_iter()
+                .map(|(function_index, reason)| SkippedFunction {
+                    module_index,
+                    function_index,
+                    reason,
+                }),
+        );
+    Some(module)
+}
+
+/// Functions whose call sites may carry an env var name, split by how the
+/// name is passed to the underlying import. Each function maps to the nearest
+/// import it reaches.
+#[derive(Default)]
+pub struct EnvCallChains {
+    /// Functions reaching an import that takes `(ptr, len)` strings.
+    pub ptr_len: HashMap<FunctionId, FunctionId>,
+    /// Functions reaching an imported C `getenv` that takes a NUL-terminated pointer.
+    pub c_str: HashMap<FunctionId, FunctionId>,
+}
+
+/// `module.name` of every imported function, for reporting hit sinks.
+fn function_import_names(module: &walrus::Module) -> HashMap<FunctionId, String> {
+    module
+        .imports
+        .iter()
+        .filter_map(|import| match import.kind {
+            ImportKind::Function(fid) => Some((fid, format!("{}.{}", import.module, import.name))),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The function's symbol from the `name` custom section, if the module has
+/// one.
+fn local_func_name(module: &walrus::Module, func: FunctionId) -> Option<String> {
+    module.funcs.get(func).name.clone()
+}
+
+/// Splice passive data segments into the memory map wherever a function
+/// copies them in with `memory.init`, and clear regions a function
+/// overwrites with `memory.fill`, where the operands are constant.
+///
+/// This is a separate walk over every function, before the real one, so a
+/// string table copied in by an init function is visible to all callers
+/// regardless of function order.
+fn replay_memory_writes(
+    module: &walrus::Module,
+    config: &ScanConfig,
+    memory_map: &mut MemoryMap,
+    globals: &HashMap<walrus::GlobalId, i32>,
+) {
+    if !module.data.iter().any(walrus::Data::is_passive) && !fills_memory(module) {
+        return;
+    }
+    let chains = EnvCallChains::default();
+    let mut writes = Vec::new();
+    for (_, local_func) in module.funcs.iter_local() {
+        let ctx = WalkContext {
+            module,
+            func: local_func,
+            memory_map,
+            chains: &chains,
+            config,
+            memory64: is_memory64(module),
+            log_sink: None,
+            all_strings: false,
+        };
+        let mut found = CallSites::default();
+        walk_seq(
+            &ctx,
+            local_func.entry_block(),
+            &mut StackState::new(),
+            &mut globals.clone(),
+            &mut found,
+        );
+        writes.extend(found.memory_writes);
+    }
+    apply_memory_writes(module, memory_map, &writes);
+}
+
+/// Whether any function uses `memory.fill`.
+fn fills_memory(module: &walrus::Module) -> bool {
+    module
+        .funcs
+        .iter_local()
+        .any(|(_, func)| seq_fills_memory(func, func.entry_block()))
+}
+
+/// Whether an instruction sequence, or one nested in it, uses `memory.fill`.
+fn seq_fills_memory(func: &walrus::LocalFunction, seq_id: InstrSeqId) -> bool {
+    func.block(seq_id)
+        .instrs
+        .iter()
+        .any(|(instr, _loc)| match instr {
+            Instr::MemoryFill(_) => true,
+            Instr::Block(b) => seq_fills_memory(func, b.seq),
+            Instr::Loop(l) => seq_fills_memory(func, l.seq),
+            Instr::IfElse(ie) => {
+                seq_fills_memory(func, ie.consequent) || seq_fills_memory(func, ie.alternative)
+            }
+            _ => false,
+        })
 }
 
 /// Analyze a single core WASM module for env var references
 /// using call-graph-based taint analysis.
-fn analyze_module(module: &walrus::Module, env_vars: &mut HashSet<String>) {
+fn analyze_module(
+    module: &walrus::Module,
+    module_index: usize,
+    data_offsets: &[usize],
+    config: &ScanConfig,
+    scan_stats: &mut ScanStats,
+    on_hit: &mut dyn FnMut(EnvVarHit),
+) {
     // Find all env-related imports — if none, this module doesn't use env vars
-    let env_funcs = find_env_imports(module);
-    if env_funcs.is_empty() {
+    let (env_funcs, getenv_funcs) = find_name_taking_imports(module, config);
+    let log_sink = config
+        .scan_logs
+        .then(|| find_fd_write_import(module))
+        .flatten();
+    if env_funcs.is_empty() && getenv_funcs.is_empty() && log_sink.is_none() {
         return;
     }
 
-    // Build the transitive call chain from all env imports
-    let env_call_chain = build_env_call_chain(module, &env_funcs);
+    // Build the transitive call chains from all env imports
+    let reverse_graph = build_reverse_call_graph(module);
+    let chains = EnvCallChains {
+        ptr_len: build_env_call_chain(&reverse_graph, &env_funcs),
+        c_str: build_env_call_chain(&reverse_graph, &getenv_funcs),
+    };
 
-    let memory_map = build_memory_map(module);
-    let mut global_values = collect_globals(module);
+    let mut memory_map = build_memory_map(module);
+    let global_values = collect_globals(module);
+    replay_memory_writes(module, config, &mut memory_map, &global_values);
+    let import_names = function_import_names(module);
 
-    for (_func_id, local_func) in module.funcs.iter_local() {
-        let entry = local_func.entry_block();
+    // Per function: the env-chain calls it makes
+    let mut env_calls = Vec::new();
+
+    for (func_id, local_func) in module.funcs.iter_local() {
+        let ctx = WalkContext {
+            module,
+            func: local_func,
+            memory_map: &memory_map,
+            chains: &chains,
+            config,
+            memory64: is_memory64(module),
+            log_sink,
+            all_strings: false,
+        };
         let mut state = StackState::new();
+        let mut found = CallSites::default();
+        // Each function starts from the globals' initial values; what another
+        // function stores depends on the order they run in
         walk_seq(
-            local_func,
-            entry,
+            &ctx,
+            local_func.entry_block(),
             &mut state,
-            &mut global_values,
-            &memory_map,
-            &env_call_chain,
-            env_vars,
+            &mut global_values.clone(),
+            &mut found,
         );
+
+        // walrus allocates imported functions first, so the arena index
+        // matches the function's index in the original binary.
+        let function_index = func_id.index() as u32;
+        let function_name = local_func_name(module, func_id);
+        scan_stats.functions_analyzed += 1;
+        scan_stats.env_call_sites += found.env_calls.len();
+        scan_stats.strings_read += found.args.len() + found.rejected.len();
+        scan_stats.strings_rejected += found.rejected.len();
+        env_calls.push((function_index, found.env_calls));
+        scan_stats.rejected_candidates.extend(
+            found
+                .rejected
+                .into_iter()
+                .map(|(candidate, reason)| (candidate, reason.to_string())),
+        );
+        let mut defaults: HashMap<usize, String> = found.defaults.into_iter().collect();
+        for (index, arg) in found.args.into_iter().enumerate() {
+            let (score, confidence) = classify_env_var(&arg.value, true, &config.extra_keywords);
+            on_hit(EnvVarHit {
+                name: arg.value,
+                ptr: Some(arg.ptr),
+                file_offset: file_offset(&memory_map, data_offsets, arg.ptr),
+                len: arg.len,
+                module_index,
+                function_index: Some(function_index),
+                function_name: function_name.clone(),
+                source: HitSource::CallSite,
+                rule: arg.rule,
+                confidence,
+                score,
+                sink: import_names.get(&arg.sink).cloned(),
+                default: defaults.remove(&index),
+                suspicious_overlap: false,
+                corroborated: false,
+            });
+        }
+        for arg in found.logged {
+            let (score, confidence) = classify_env_var(&arg.value, false, &config.extra_keywords);
+            on_hit(EnvVarHit {
+                name: arg.value,
+                ptr: Some(arg.ptr),
+                file_offset: file_offset(&memory_map, data_offsets, arg.ptr),
+                len: arg.len,
+                module_index,
+                function_index: Some(function_index),
+                function_name: function_name.clone(),
+                source: HitSource::LogMessage,
+                rule: arg.rule,
+                confidence,
+                score,
+                sink: import_names.get(&arg.sink).cloned(),
+                default: None,
+                suspicious_overlap: false,
+                corroborated: false,
+            });
+        }
+    }
+
+    record_unresolved_reads(module_index, env_calls, scan_stats);
+}
+
+/// Offset in the scanned file of the byte at `addr`, if it was initialized
+/// from a data segment whose offset is in `data_offsets`.
+fn file_offset(memory_map: &MemoryMap, data_offsets: &[usize], addr: u32) -> Option<usize> {
+    let (data_index, offset) = memory_map.origin(addr)?;
+    Some(data_offsets.get(data_index)? + offset)
+}
+
+/// A callee that received an env var name somewhere is a getenv-like entry
+/// point; calls to it with no readable string read a variable we couldn't
+/// name. `env_calls` holds each function's env-chain calls.
+fn record_unresolved_reads(
+    module_index: usize,
+    env_calls: Vec<(u32, Vec<EnvCall>)>,
+    scan_stats: &mut ScanStats,
+) {
+    let name_taking: HashSet<FunctionId> = env_calls
+        .iter()
+        .flat_map(|(_, calls)| calls)
+        .filter(|call| call.named)
+        .map(|call| call.callee)
+        .collect();
+    for (function_index, calls) in env_calls {
+        if calls
+            .iter()
+            .any(|call| !call.read && name_taking.contains(&call.callee))
+        {
+            scan_stats
+                .unresolved_env_reads
+                .push((module_index, function_index));
+        }
+    }
+}
+
+/// Report `SCREAMING_SNAKE_CASE` export names as low-confidence hits.
+/// Some builds only surface config keys as the names of exported items.
+fn scan_export_names(
+    module: &walrus::Module,
+    module_index: usize,
+    config: &ScanConfig,
+    on_hit: &mut dyn FnMut(EnvVarHit),
+) {
+    for export in module.exports.iter() {
+        let name = &export.name;
+        let Some(rule) = untraced_name_rule(name, config) else {
+            continue;
+        };
+        let (function_index, function_name) = match export.item {
+            ExportItem::Function(fid) => (Some(fid.index() as u32), local_func_name(module, fid)),
+            _ => (None, None),
+        };
+        let (score, confidence) = classify_env_var(name, false, &config.extra_keywords);
+        on_hit(EnvVarHit {
+            name: name.clone(),
+            ptr: None,
+            file_offset: None,
+            len: name.len() as u32,
+            module_index,
+            function_index,
+            function_name,
+            source: HitSource::ExportName,
+            rule,
+            confidence,
+            score,
+            sink: None,
+            default: None,
+            suspicious_overlap: false,
+            corroborated: false,
+        });
+    }
+}
+
+/// Report `SCREAMING_SNAKE_CASE` strings anywhere in the data segments as
+/// low-confidence hits. Used for relocatable modules, where nothing ties a
+/// string to a call site.
+fn scan_data_strings(
+    module: &walrus::Module,
+    module_index: usize,
+    data_offsets: &[usize],
+    config: &ScanConfig,
+    on_hit: &mut dyn FnMut(EnvVarHit),
+) {
+    for data in module.data.iter() {
+        let data_offset = data_offsets.get(data.id().index());
+        let words = data
+            .value
+            .split(|&b| !(b.is_ascii_alphanumeric() || b == b'_'))
+            .filter_map(|word| std::str::from_utf8(word).ok());
+        for name in words {
+            let word_offset = name.as_ptr() as usize - data.value.as_ptr() as usize;
+            let Some(rule) = untraced_name_rule(name, config) else {
+                continue;
+            };
+            let (score, confidence) = classify_env_var(name, false, &config.extra_keywords);
+            on_hit(EnvVarHit {
+                name: name.to_string(),
+                ptr: None,
+                file_offset: data_offset.map(|offset| offset + word_offset),
+                len: name.len() as u32,
+                module_index,
+                function_index: None,
+                function_name: None,
+                source: HitSource::DataString,
+                rule,
+                confidence,
+                score,
+                sink: None,
+                default: None,
+                suspicious_overlap: false,
+                corroborated: false,
+            });
+        }
+    }
+}
+
+/// Report `SCREAMING_SNAKE_CASE` strings in the DWARF `.debug_str` section
+/// (identifiers from the source) as low-confidence hits.
+fn scan_debug_strings(
+    module: &walrus::Module,
+    module_index: usize,
+    config: &ScanConfig,
+    on_hit: &mut dyn FnMut(EnvVarHit),
+) {
+    let debug_str = module
+        .debug
+        .dwarf
+        .debug_str
+        .borrow(|data| EndianSlice::new(data, LittleEndian));
+    let mut reader = *debug_str.reader();
+    while let Ok(string) = reader.read_null_terminated_slice() {
+        let Ok(name) = std::str::from_utf8(string.slice()) else {
+            continue;
+        };
+        let Some(rule) = untraced_name_rule(name, config) else {
+            continue;
+        };
+        let (score, confidence) = classify_env_var(name, false, &config.extra_keywords);
+        on_hit(EnvVarHit {
+            name: name.to_string(),
+            ptr: None,
+            file_offset: None,
+            len: name.len() as u32,
+            module_index,
+            function_index: None,
+            function_name: None,
+            source: HitSource::DebugInfo,
+            rule,
+            confidence,
+            score,
+            sink: None,
+            default: None,
+            suspicious_overlap: false,
+            corroborated: false,
+        });
     }
 }
 
@@ -85,14 +666,73 @@ fn find_env_imports(module: &walrus::Module) -> HashSet<FunctionId> {
     env_funcs
 }
 
-/// Build the set of all `FunctionIds` that transitively call any env-related import.
-/// These are the "env-touching" functions — any call TO one of these functions
-/// is a potential env var access point.
-fn build_env_call_chain(
+/// Whether a module imports an API that hands it the entire environment
+/// rather than looking up one variable.
+fn reads_whole_environment(module: &walrus::Module) -> bool {
+    module.imports.iter().any(|import| {
+        matches!(import.kind, ImportKind::Function(_))
+            && ((import.module == "wasi_snapshot_preview1" && import.name == "environ_get")
+                || (import.module.starts_with("wasi:cli/environment")
+                    && import.name == "get-environment"))
+    })
+}
+
+/// Find imported C `getenv` functions, as used by Emscripten side modules
+/// (`dylink.0`) that resolve libc from the main module at load time.
+fn find_c_getenv_imports(module: &walrus::Module) -> HashSet<FunctionId> {
+    let mut getenv_funcs = HashSet::new();
+    for import in module.imports.iter() {
+        if let ImportKind::Function(fid) = import.kind {
+            if import.module == "env" && import.name == "getenv" {
+                getenv_funcs.insert(fid);
+            }
+        }
+    }
+    getenv_funcs
+}
+
+/// Find the WASI preview1 `fd_write` import, through which messages written
+/// to stdout/stderr pass.
+fn find_fd_write_import(module: &walrus::Module) -> Option<FunctionId> {
+    module.imports.iter().find_map(|import| match import.kind {
+        ImportKind::Function(fid)
+            if import.module == "wasi_snapshot_preview1" && import.name == "fd_write" =>
+        {
+            Some(fid)
+        }
+        _ => None,
+    })
+}
+
+/// The env imports taking a `(ptr, len)` name and the C `getenv` imports of
+/// a module: the built-in ones plus any the configured classifier adds.
+fn find_name_taking_imports(
     module: &walrus::Module,
-    env_funcs: &HashSet<FunctionId>,
-) -> HashSet<FunctionId> {
-    // Step 1: Build reverse call graph (callee → set of callers)
+    config: &ScanConfig,
+) -> (HashSet<FunctionId>, HashSet<FunctionId>) {
+    let mut env_funcs = find_env_imports(module);
+    let mut getenv_funcs = find_c_getenv_imports(module);
+    let Some(classifier) = &config.callee_classifier else {
+        return (env_funcs, getenv_funcs);
+    };
+    for import in module.imports.iter() {
+        if let ImportKind::Function(fid) = import.kind {
+            match classifier.classify(&import.module, &import.name) {
+                CalleeKind::StringSink => {
+                    env_funcs.insert(fid);
+                }
+                CalleeKind::Getenv => {
+                    getenv_funcs.insert(fid);
+                }
+                CalleeKind::Ignore => {}
+            }
+        }
+    }
+    (env_funcs, getenv_funcs)
+}
+
+/// Build the reverse call graph (callee → set of callers) of a module.
+fn build_reverse_call_graph(module: &walrus::Module) -> HashMap<FunctionId, HashSet<FunctionId>> {
     let mut reverse_graph: HashMap<FunctionId, HashSet<FunctionId>> = HashMap::new();
 
     for func in module.funcs.iter() {
@@ -105,19 +745,36 @@ fn build_env_call_chain(
         }
     }
 
-    // Step 2: Unbounded BFS from all env import functions
-    let mut chain = HashSet::new();
+    reverse_graph
+}
+
+/// Build the set of all `FunctionIds` that transitively call any env-related import.
+/// These are the "env-touching" functions — any call TO one of these functions
+/// is a potential env var access point.
+///
+/// Each function is mapped to the import it reaches in the fewest calls (ties
+/// go to the lowest import index), which becomes the hit's `sink`.
+fn build_env_call_chain(
+    reverse_graph: &HashMap<FunctionId, HashSet<FunctionId>>,
+    env_funcs: &HashSet<FunctionId>,
+) -> HashMap<FunctionId, FunctionId> {
+    // Unbounded BFS from all env import functions
+    let mut chain = HashMap::new();
     let mut queue: VecDeque<FunctionId> = VecDeque::new();
 
-    for &env_func in env_funcs {
-        chain.insert(env_func);
+    let mut seeds: Vec<FunctionId> = env_funcs.iter().copied().collect();
+    seeds.sort();
+    for env_func in seeds {
+        chain.insert(env_func, env_func);
         queue.push_back(env_func);
     }
 
     while let Some(func_id) = queue.pop_front() {
+        let sink = chain[&func_id];
         if let Some(callers) = reverse_graph.get(&func_id) {
             for &caller in callers {
-                if chain.insert(caller) {
+                if let Entry::Vacant(entry) = chain.entry(caller) {
+                    entry.insert(sink);
                     queue.push_back(caller);
                 }
             }
@@ -135,7 +792,8 @@ fn collect_call_targets(func: &walrus::LocalFunction) -> HashSet<FunctionId> {
     targets
 }
 
-/// Recursively collect Call targets from an instruction sequence.
+/// Recursively collect direct call targets (including tail calls) from an
+/// instruction sequence.
 fn collect_calls_in_seq(
     func: &walrus::LocalFunction,
     seq_id: InstrSeqId,
@@ -147,6 +805,9 @@ fn collect_calls_in_seq(
             Instr::Call(c) => {
                 targets.insert(c.func);
             }
+            Instr::ReturnCall(rc) => {
+                targets.insert(rc.func);
+            }
             Instr::Block(b) => collect_calls_in_seq(func, b.seq, targets),
             Instr::Loop(l) => collect_calls_in_seq(func, l.seq, targets),
             Instr::IfElse(ie) => {
@@ -0,0 +1,150 @@
+//! Memoized scanning for callers that see the same bytes repeatedly.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::{scan_wasm_bytes_with_config, Result, ScanConfig};
+
+/// Wraps [`scan_wasm_bytes_with_config`] with an LRU cache of results keyed
+/// by a hash of the input.
+///
+/// Useful for services that receive duplicate uploads. Only successful scans
+/// are cached. Entries are keyed by a 64-bit hash of the bytes, not the bytes
+/// themselves, so memory use is bounded by the names stored.
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::CachingScanner;
+///
+/// let mut scanner = CachingScanner::new(64);
+/// let wasm_data = std::fs::read("./my-component.wasm").unwrap();
+/// let first = scanner.scan(&wasm_data).unwrap();
+/// // Served from the cache
+/// assert_eq!(scanner.scan(&wasm_data).unwrap(), first);
+/// ```
+#[derive(Debug)]
+pub struct CachingScanner {
+    config: ScanConfig,
+    capacity: usize,
+    entries: HashMap<u64, Entry>,
+    /// Bumped on every lookup; entries remember when they were last used.
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    names: Vec<String>,
+    last_used: u64,
+}
+
+impl CachingScanner {
+    /// A scanner with the default [`ScanConfig`] that keeps the results of at
+    /// most `capacity` distinct inputs. A capacity of 0 disables caching.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(ScanConfig::default(), capacity)
+    }
+
+    /// Like [`CachingScanner::new`], scanning with `config`.
+    pub fn with_config(config: ScanConfig, capacity: usize) -> Self {
+        Self {
+            config,
+            capacity,
+            entries: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Scan `wasm_bytes`, or return the cached result for identical input.
+    pub fn scan(&mut self, wasm_bytes: &[u8]) -> Result<Vec<String>> {
+        let key = content_hash(wasm_bytes);
+        self.clock += 1;
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.last_used = self.clock;
+            return Ok(entry.names.clone());
+        }
+
+        let names = scan_wasm_bytes_with_config(wasm_bytes, &self.config)?;
+        if self.capacity > 0 {
+            if self.entries.len() >= self.capacity {
+                self.evict_least_recently_used();
+            }
+            self.entries.insert(
+                key,
+                Entry {
+                    names: names.clone(),
+                    last_used: self.clock,
+                },
+            );
+        }
+        Ok(names)
+    }
+
+    /// Number of cached results.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop every cached result.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(&key, _)| key);
+        if let Some(key) = oldest {
+            self.entries.remove(&key);
+        }
+    }
+}
+
+fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(name: &str) -> Vec<u8> {
+        let len = name.len();
+        wat::parse_str(format!(
+            r#"(module
+                (import "wasi:cli/environment@0.2.0" "get-environment" (func $get (param i32 i32)))
+                (memory 1)
+                (data (i32.const 1024) "{name}")
+                (func (export "run") (call $get (i32.const 1024) (i32.const {len}))))"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let (a, b, c) = (
+            module("API_KEY"),
+            module("DATABASE_URL"),
+            module("LOG_LEVEL"),
+        );
+        let mut scanner = CachingScanner::new(2);
+
+        assert_eq!(scanner.scan(&a).unwrap(), vec!["API_KEY"]);
+        assert_eq!(scanner.scan(&b).unwrap(), vec!["DATABASE_URL"]);
+        // Touch `a` so `b` is the least recently used
+        assert_eq!(scanner.scan(&a).unwrap(), vec!["API_KEY"]);
+        assert_eq!(scanner.scan(&c).unwrap(), vec!["LOG_LEVEL"]);
+
+        assert_eq!(scanner.len(), 2);
+        assert!(scanner.entries.contains_key(&content_hash(&a)));
+        assert!(!scanner.entries.contains_key(&content_hash(&b)));
+        assert!(scanner.entries.contains_key(&content_hash(&c)));
+    }
+}
@@ -0,0 +1,78 @@
+//! Coarse categorization of detected variable names.
+//!
+//! Categories are derived purely from the name's underscore-separated
+//! segments, so they're a hint for reporting (e.g. "don't bake this into an
+//! image") rather than a guarantee about the value's contents.
+
+/// What kind of value a variable most likely holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Category {
+    /// Credentials and key material (`API_KEY`, `DB_PASSWORD`, `JWT_SECRET`).
+    Secret,
+    /// Addresses of other services (`DATABASE_URL`, `AUTH_ENDPOINT`).
+    Url,
+    /// Anything else.
+    Generic,
+}
+
+/// Name segments that mark a variable as holding a secret.
+const SECRET_SEGMENTS: &[&str] = &[
+    "KEY",
+    "SECRET",
+    "TOKEN",
+    "PASSWORD",
+    "PASSWD",
+    "PASS",
+    "PWD",
+    "CREDENTIAL",
+    "CREDENTIALS",
+    "PRIVATE",
+];
+
+/// Name segments that mark a variable as holding a service address.
+const URL_SEGMENTS: &[&str] = &["URL", "URI", "ENDPOINT", "DSN"];
+
+impl Category {
+    /// Categorize a variable name by its underscore-separated segments
+    /// (case-insensitive), so `API_KEY` is a secret but `KEYBOARD_LAYOUT` is
+    /// not. A name with both secret and URL segments is a secret.
+    pub fn of(name: &str) -> Self {
+        let has_segment = |segments: &[&str]| {
+            name.split('_').any(|segment| {
+                segments
+                    .iter()
+                    .any(|wanted| segment.eq_ignore_ascii_case(wanted))
+            })
+        };
+        if has_segment(SECRET_SEGMENTS) {
+            Category::Secret
+        } else if has_segment(URL_SEGMENTS) {
+            Category::Url
+        } else {
+            Category::Generic
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_of() {
+        assert_eq!(Category::of("API_KEY"), Category::Secret);
+        assert_eq!(Category::of("JWT_SECRET"), Category::Secret);
+        assert_eq!(Category::of("DB_PASS"), Category::Secret);
+        assert_eq!(Category::of("auth_token"), Category::Secret);
+
+        assert_eq!(Category::of("DATABASE_URL"), Category::Url);
+        assert_eq!(Category::of("redis_uri"), Category::Url);
+        assert_eq!(Category::of("SECRET_URL"), Category::Secret);
+
+        assert_eq!(Category::of("LOG_LEVEL"), Category::Generic);
+        assert_eq!(Category::of("KEYBOARD_LAYOUT"), Category::Generic);
+        assert_eq!(Category::of("PASSTHROUGH_MODE"), Category::Generic);
+    }
+}
@@ -0,0 +1,543 @@
+//! Command-line arguments.
+
+use std::ffi::OsString;
+use std::io::IsTerminal;
+
+#[cfg(feature = "config")]
+use clap::parser::{ArgMatches, ValueSource};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use wasm2env::{Confidence, ScanConfig};
+
+use crate::render::{Format, RenderOptions};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "wasm2env",
+    version,
+    about = "Detects environment variables by analyzing WASM bytecode.",
+    arg_required_else_help = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+    /// Read options from this TOML file instead of `./wasm2env.toml`;
+    /// options given on the command line win (requires the `config`
+    /// feature)
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<String>,
+}
+
+/// Options read from `wasm2env.toml` or `--config`: a `[scan]` table with
+/// the scan options and an `[output]` table with the render settings, keyed
+/// like the flags, e.g. `min-functions = 2` or `format = "json"`.
+#[cfg(feature = "config")]
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    pub scan: ScanOptions,
+    pub output: RenderOptions,
+}
+
+#[cfg(feature = "config")]
+impl Cli {
+    /// Take every option that wasn't given on the command line from `file`.
+    /// Only `scan` uses the `[output]` table.
+    pub fn fill_from(&mut self, file: ConfigFile, matches: &ArgMatches) {
+        let Some((_, matches)) = matches.subcommand() else {
+            return;
+        };
+        match &mut self.command {
+            Command::Scan(args) => args.fill_from(file, matches),
+            Command::Diff(DiffArgs { scan, .. })
+            | Command::Validate(ValidateArgs { scan, .. })
+            | Command::Strings(StringsArgs { scan, .. })
+            | Command::CheckEnv(CheckEnvArgs { scan, .. }) => scan.fill_from(file.scan, matches),
+        }
+    }
+}
+
+/// Whether the argument `id` was given on the command line, as opposed to
+/// taking its default.
+#[cfg(feature = "config")]
+fn given(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Report the environment variables a module reads (the default command)
+    Scan(ScanArgs),
+    /// List variables added or removed between two versions of a module
+    Diff(DiffArgs),
+    /// Check that a module reads exactly the variables declared in a file
+    Validate(ValidateArgs),
+    /// List every string passed to an env API, including rejected ones
+    Strings(StringsArgs),
+    /// Exit non-zero if a detected variable has no value in an env file
+    CheckEnv(CheckEnvArgs),
+}
+
+/// Where to read the module from.
+#[derive(Debug, Args)]
+pub struct Input {
+    /// WASM module or component to scan, possibly gzipped, or `-` for stdin
+    /// (also read when no file is given and stdin isn't a terminal). `scan`
+    /// also takes a directory, searched recursively for `.wasm` and
+    /// `.wasm.gz` files
+    #[arg(value_name = "WASM_FILE")]
+    pub file: Option<String>,
+    /// Fetch the module over HTTP(S) instead of reading a file (requires the
+    /// `net` feature)
+    #[arg(long, value_name = "URL", conflicts_with = "file")]
+    pub url: Option<String>,
+}
+
+/// Options that change what the scan detects.
+#[allow(clippy::struct_excessive_bools)] // mirrors `ScanConfig`
+#[derive(Debug, Args)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(
+    feature = "config",
+    serde(default, deny_unknown_fields, rename_all = "kebab-case")
+)]
+pub struct ScanOptions {
+    /// Also report env-like export names (low confidence)
+    #[arg(long)]
+    pub scan_exports: bool,
+    /// Only report names used in at least N distinct functions (several call
+    /// sites in one function count once)
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    pub min_functions: usize,
+    /// Read names passed as a bare pointer up to the next NUL (for frameworks
+    /// that intern strings into one blob)
+    #[arg(long)]
+    pub interned_strings: bool,
+    /// Read a lone pointer argument up to the next NUL when no (ptr, len)
+    /// pair is found (for C wrappers around WASI env APIs)
+    #[arg(long)]
+    pub c_strings: bool,
+    /// Also report env-like words in messages written with WASI `fd_write`
+    /// (low confidence)
+    #[arg(long)]
+    pub scan_logs: bool,
+    /// Also report env-like identifiers from DWARF debug info (low
+    /// confidence; may name code the optimizer removed)
+    #[arg(long)]
+    pub debug_info: bool,
+    /// Only report names found by two detection methods, e.g. a call site
+    /// and a logged message (needs --scan-logs, --scan-exports or
+    /// --debug-info)
+    #[arg(long)]
+    pub corroborated_only: bool,
+    /// Read names that run past the initialized data up to the gap
+    #[arg(long)]
+    pub partial_strings: bool,
+    /// Accept lowercase names ending in a config keyword, like
+    /// `database_url`, where only upper-case names are otherwise trusted
+    #[arg(long)]
+    pub allow_lowercase: bool,
+    /// Accept dotted config keys like `app.database.url`
+    #[arg(long)]
+    pub allow_dotted: bool,
+    /// Accept dash-separated keys like `FEATURE-FLAG-X`
+    #[arg(long)]
+    pub allow_dashed: bool,
+    /// Report names that differ only in case once, spelled with the most
+    /// upper-case letters
+    #[arg(long)]
+    pub dedup_case_insensitive: bool,
+    /// Never report names matching this glob (`*` and `?` wildcards,
+    /// case-insensitive); repeatable
+    #[arg(long, value_name = "PATTERN")]
+    pub ignore: Vec<String>,
+    /// Never report names this regex matches (requires the `regex`
+    /// feature); repeatable
+    #[arg(long, value_name = "REGEX", value_parser = parse_regex)]
+    #[cfg_attr(feature = "config", serde(deserialize_with = "deserialize_regexes"))]
+    pub ignore_regex: Vec<IgnoreRegex>,
+    /// Drop hits below this confidence
+    #[arg(long, value_name = "low|medium|high", default_value = "low", value_parser = parse_confidence)]
+    pub min_confidence: Confidence,
+}
+
+fn parse_confidence(s: &str) -> Result<Confidence, String> {
+    match s {
+        "low" => Ok(Confidence::Low),
+        "medium" => Ok(Confidence::Medium),
+        "high" => Ok(Confidence::High),
+        other => Err(format!(
+            "unknown confidence '{other}' (expected: low, medium, high)"
+        )),
+    }
+}
+
+#[cfg(feature = "regex")]
+type IgnoreRegex = regex::Regex;
+// Without the feature every `--ignore-regex` value is rejected, so none exist
+#[cfg(not(feature = "regex"))]
+type IgnoreRegex = std::convert::Infallible;
+
+#[cfg(feature = "regex")]
+fn parse_regex(s: &str) -> Result<IgnoreRegex, regex::Error> {
+    regex::Regex::new(s)
+}
+
+#[cfg(not(feature = "regex"))]
+fn parse_regex(_s: &str) -> Result<IgnoreRegex, String> {
+    Err("requires wasm2env to be built with the `regex` feature".to_string())
+}
+
+/// `ignore-regex` in a config file, compiled like `--ignore-regex` values.
+#[cfg(feature = "config")]
+fn deserialize_regexes<'de, D>(deserializer: D) -> Result<Vec<IgnoreRegex>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let patterns: Vec<String> = serde::Deserialize::deserialize(deserializer)?;
+    patterns
+        .iter()
+        .map(|pattern| parse_regex(pattern).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+/// What clap gives options left off the command line, so a config file
+/// that leaves an option out behaves the same.
+impl Default for ScanOptions {
+    fn default() -> Self {
+        let command = Self::augment_args(clap::Command::new("wasm2env"));
+        Self::from_arg_matches(&command.get_matches_from(["wasm2env"]))
+            .expect("no scan option is required")
+    }
+}
+
+impl ScanOptions {
+    pub fn config(&self) -> ScanConfig {
+        let config = ScanConfig::new()
+            .scan_exports(self.scan_exports)
+            .min_functions(self.min_functions)
+            .interned_strings(self.interned_strings)
+            .c_strings(self.c_strings)
+            .scan_logs(self.scan_logs)
+            .debug_info(self.debug_info)
+            .corroborated_only(self.corroborated_only)
+            .partial_strings(self.partial_strings)
+            .allow_lowercase(self.allow_lowercase)
+            .allow_dotted(self.allow_dotted)
+            .allow_dashed(self.allow_dashed)
+            .dedup_case_insensitive(self.dedup_case_insensitive)
+            .min_confidence(self.min_confidence)
+            .ignore(&self.ignore);
+        #[cfg(feature = "regex")]
+        let config = self
+            .ignore_regex
+            .iter()
+            .fold(config, |config, regex| config.ignore_regex(regex.clone()));
+        config
+    }
+
+    /// Take every option that wasn't given on the command line from `file`.
+    #[cfg(feature = "config")]
+    pub fn fill_from(&mut self, file: Self, matches: &ArgMatches) {
+        // Spelled out so a new option can't be forgotten here
+        let Self {
+            scan_exports,
+            min_functions,
+            interned_strings,
+            c_strings,
+            scan_logs,
+            debug_info,
+            corroborated_only,
+            partial_strings,
+            allow_lowercase,
+            allow_dotted,
+            allow_dashed,
+            dedup_case_insensitive,
+            ignore,
+            ignore_regex,
+            min_confidence,
+        } = file;
+        macro_rules! fill {
+            ($($option:ident),*) => {
+                $(
+                    if !given(matches, stringify!($option)) {
+                        self.$option = $option;
+                    }
+                )*
+            };
+        }
+        fill!(
+            scan_exports,
+            min_functions,
+            interned_strings,
+            c_strings,
+            scan_logs,
+            debug_info,
+            corroborated_only,
+            partial_strings,
+            allow_lowercase,
+            allow_dotted,
+            allow_dashed,
+            dedup_case_insensitive,
+            ignore,
+            ignore_regex,
+            min_confidence
+        );
+    }
+}
+
+// Independent on/off switches, as flags usually are
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Args)]
+pub struct ScanArgs {
+    #[command(flatten)]
+    pub input: Input,
+    #[command(flatten)]
+    pub scan: ScanOptions,
+    /// Output format
+    #[arg(
+        long,
+        value_name = "plain|dockerfile|category-summary|json|sarif|names",
+        default_value = "plain"
+    )]
+    pub format: Format,
+    /// Print only the names, one per line (same as `--format names`)
+    #[arg(short, long, conflicts_with_all = ["format", "interactive"])]
+    pub quiet: bool,
+    /// Report at most N variables
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+    /// Print names lowercased (for case-insensitive platforms)
+    #[arg(long)]
+    pub lowercase: bool,
+    /// Also print how many data-section bytes the names take up, and how
+    /// many functions, call sites and strings the scan went through
+    #[arg(long)]
+    pub stats: bool,
+    /// When scanning a directory (requires the `dir` feature), report each
+    /// `.wasm` file separately instead of the union of their variables
+    #[arg(long, conflicts_with_all = ["interactive", "watch"])]
+    pub per_file: bool,
+    /// Browse the results in a terminal UI (requires the `tui` feature)
+    #[arg(long, conflicts_with = "watch")]
+    pub interactive: bool,
+    /// Rescan and reprint whenever the file changes (requires the `watch`
+    /// feature)
+    #[arg(long, conflicts_with = "url")]
+    pub watch: bool,
+    /// Exit with status 1 if any variable is detected
+    #[arg(long, conflicts_with_all = ["interactive", "watch"])]
+    pub fail_on_detect: bool,
+    /// Exit with status 1 if a detected variable isn't listed in FILE (one
+    /// name per line; `NAME=value` lines and `#` comments are fine)
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["interactive", "watch", "fail_on_detect"]
+    )]
+    pub require: Option<String>,
+    /// Write a `.env` template (`NAME=` per variable) to PATH; an existing
+    /// file keeps its values and only gets the new names appended
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["interactive", "watch"])]
+    pub emit_dotenv: Option<String>,
+    /// Same as the `check-env` command; kept for existing scripts
+    #[arg(long, value_name = "FILE|-", hide = true, conflicts_with_all = ["interactive", "watch"])]
+    pub check_env: Option<String>,
+}
+
+impl ScanArgs {
+    pub fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            format: if self.quiet {
+                Format::Names
+            } else {
+                self.format
+            },
+            limit: self.limit,
+            lowercase: self.lowercase,
+        }
+    }
+
+    /// Take every scan option and render setting that wasn't given on the
+    /// command line from `file`.
+    #[cfg(feature = "config")]
+    pub fn fill_from(&mut self, file: ConfigFile, matches: &ArgMatches) {
+        self.scan.fill_from(file.scan, matches);
+        let RenderOptions {
+            format,
+            limit,
+            lowercase,
+        } = file.output;
+        if !given(matches, "format") {
+            self.format = format;
+        }
+        if !given(matches, "limit") {
+            self.limit = limit;
+        }
+        if !given(matches, "lowercase") {
+            self.lowercase = lowercase;
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// The old and new module, or just the module with --since-git
+    #[arg(value_name = "WASM_FILE", num_args = 1..=2, required = true)]
+    pub files: Vec<String>,
+    /// Compare with the file's version at this git revision (requires the
+    /// `git` feature)
+    #[arg(long, value_name = "REV")]
+    pub since_git: Option<String>,
+    #[command(flatten)]
+    pub scan: ScanOptions,
+}
+
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    #[command(flatten)]
+    pub input: Input,
+    /// File declaring the expected variables, one per line (`NAME=value`
+    /// lines and `#` comments are fine, so a `.env` file works)
+    #[arg(long, value_name = "FILE")]
+    pub expected: String,
+    #[command(flatten)]
+    pub scan: ScanOptions,
+}
+
+#[derive(Debug, Args)]
+pub struct StringsArgs {
+    #[command(flatten)]
+    pub input: Input,
+    #[command(flatten)]
+    pub scan: ScanOptions,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckEnvArgs {
+    #[command(flatten)]
+    pub input: Input,
+    /// `.env` file holding the values, or `-` for the current environment
+    #[arg(long, value_name = "FILE|-")]
+    pub env_file: String,
+    #[command(flatten)]
+    pub scan: ScanOptions,
+}
+
+/// Insert `scan` when no command is given, so `wasm2env <file>`,
+/// `wasm2env --format dockerfile <file>` and `cat app.wasm | wasm2env` keep
+/// working.
+pub fn with_default_command(mut args: Vec<OsString>) -> Vec<OsString> {
+    let command = Cli::command();
+    let implies_scan = match args.get(1) {
+        Some(first) => {
+            let first = first.to_string_lossy();
+            let is_command =
+                first == "help" || command.get_subcommands().any(|sub| sub.get_name() == first);
+            let is_global_flag = matches!(&*first, "-h" | "--help" | "-V" | "--version");
+            !is_command && !is_global_flag
+        }
+        // A bare `wasm2env` scans stdin when something is piped in
+        None => !std::io::stdin().is_terminal(),
+    };
+    if implies_scan {
+        args.insert(1, "scan".into());
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        let args = args.iter().map(OsString::from).collect();
+        Cli::try_parse_from(with_default_command(args)).unwrap()
+    }
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_bare_file_implies_scan() {
+        let Command::Scan(args) =
+            parse(&["wasm2env", "--format", "dockerfile", "app.wasm"]).command
+        else {
+            panic!("expected scan");
+        };
+        assert_eq!(args.input.file.as_deref(), Some("app.wasm"));
+        assert_eq!(args.format, Format::Dockerfile);
+
+        assert!(matches!(
+            parse(&["wasm2env", "diff", "old.wasm", "new.wasm"]).command,
+            Command::Diff(_)
+        ));
+    }
+
+    #[test]
+    fn test_file_is_optional() {
+        // Read from stdin instead
+        let Command::Scan(args) = parse(&["wasm2env", "-q"]).command else {
+            panic!("expected scan");
+        };
+        assert_eq!(args.input.file, None);
+    }
+
+    #[test]
+    fn test_quiet_prints_names() {
+        let Command::Scan(args) = parse(&["wasm2env", "-q", "app.wasm"]).command else {
+            panic!("expected scan");
+        };
+        assert_eq!(args.render_options().format, Format::Names);
+
+        let args = ["wasm2env", "-q", "--format", "json", "app.wasm"].map(OsString::from);
+        assert!(Cli::try_parse_from(with_default_command(args.to_vec())).is_err());
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_config_file() {
+        let file: ConfigFile = toml::from_str(
+            r#"
+            [scan]
+            min-functions = 2
+            ignore = ["TEST_*"]
+            min-confidence = "medium"
+
+            [output]
+            format = "category-summary"
+            limit = 10
+            "#,
+        )
+        .unwrap();
+
+        let args = [
+            "wasm2env",
+            "--min-functions",
+            "3",
+            "--format",
+            "json",
+            "app.wasm",
+        ];
+        let matches = Cli::command()
+            .get_matches_from(with_default_command(args.map(OsString::from).to_vec()));
+        let mut cli = Cli::from_arg_matches(&matches).unwrap();
+        cli.fill_from(file, &matches);
+        let Command::Scan(args) = cli.command else {
+            panic!("expected scan");
+        };
+
+        // The command line wins
+        assert_eq!(args.scan.min_functions, 3);
+        assert_eq!(args.format, Format::Json);
+        // The rest comes from the file, or keeps its default
+        assert_eq!(args.scan.ignore, ["TEST_*"]);
+        assert_eq!(args.scan.min_confidence, Confidence::Medium);
+        assert_eq!(args.limit, Some(10));
+        assert!(!args.lowercase);
+
+        let typo = toml::from_str::<ConfigFile>("[scan]\nmin-function = 2\n");
+        assert!(typo.is_err());
+    }
+}
@@ -0,0 +1,399 @@
+//! Scan configuration.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::report::Confidence;
+
+/// Options controlling how a scan runs.
+///
+/// The default configuration reproduces [`crate::scan_wasm_bytes`] exactly;
+/// every pass is opt-in, and the thresholds default to the built-in ones.
+///
+/// # Example
+/// ```
+/// use wasm2env::ScanConfig;
+///
+/// let config = ScanConfig::new().scan_exports(true);
+/// ```
+// Each flag enables an independent pass
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScanConfig {
+    pub(crate) min_length: usize,
+    pub(crate) max_length: usize,
+    pub(crate) min_word_length: usize,
+    pub(crate) min_ptr: u32,
+    pub(crate) max_string_len: u32,
+    pub(crate) extra_noise: Vec<String>,
+    pub(crate) ignore: Vec<String>,
+    #[cfg(feature = "regex")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) ignore_regex: Vec<regex::Regex>,
+    pub(crate) extra_keywords: Vec<String>,
+    pub(crate) allow_lowercase: bool,
+    pub(crate) allow_dotted: bool,
+    pub(crate) allow_dashed: bool,
+    pub(crate) dedup_case_insensitive: bool,
+    pub(crate) scan_exports: bool,
+    pub(crate) interned_strings: bool,
+    pub(crate) c_strings: bool,
+    pub(crate) min_functions: usize,
+    pub(crate) min_confidence: Confidence,
+    pub(crate) scan_logs: bool,
+    pub(crate) debug_info: bool,
+    pub(crate) corroborated_only: bool,
+    pub(crate) partial_strings: bool,
+    // Closures can't be serialized, so a deserialized config has none
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) callee_classifier: Option<CalleeClassifier>,
+}
+
+/// What a function import means to the scan, as decided by a
+/// [`ScanConfig::callee_classifier`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CalleeKind {
+    /// A C-style `getenv` taking the name as a NUL-terminated pointer.
+    Getenv,
+    /// An env or config lookup taking the name as a `(ptr, len)` pair.
+    StringSink,
+    /// Not env-related (built-in detection still applies).
+    Ignore,
+}
+
+type ClassifyFn = dyn Fn(&str, &str) -> CalleeKind + Send + Sync;
+
+/// A user-supplied import classifier.
+#[derive(Clone)]
+pub(crate) struct CalleeClassifier(Arc<ClassifyFn>);
+
+impl CalleeClassifier {
+    pub(crate) fn classify(&self, module: &str, name: &str) -> CalleeKind {
+        (self.0)(module, name)
+    }
+}
+
+impl fmt::Debug for CalleeClassifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CalleeClassifier(..)")
+    }
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 2,
+            max_length: 100,
+            min_word_length: 4,
+            min_ptr: 1,
+            max_string_len: 200,
+            extra_noise: Vec::new(),
+            ignore: Vec::new(),
+            #[cfg(feature = "regex")]
+            ignore_regex: Vec::new(),
+            extra_keywords: Vec::new(),
+            allow_lowercase: false,
+            allow_dotted: false,
+            allow_dashed: false,
+            dedup_case_insensitive: false,
+            scan_exports: false,
+            interned_strings: false,
+            c_strings: false,
+            min_functions: 1,
+            min_confidence: Confidence::Low,
+            scan_logs: false,
+            debug_info: false,
+            corroborated_only: false,
+            partial_strings: false,
+            callee_classifier: None,
+        }
+    }
+}
+
+impl ScanConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shortest name reported, in bytes (default 2).
+    #[must_use]
+    pub fn min_length(mut self, len: usize) -> Self {
+        self.min_length = len;
+        self
+    }
+
+    /// Longest name reported, in bytes (default 100).
+    #[must_use]
+    pub fn max_length(mut self, len: usize) -> Self {
+        self.max_length = len;
+        self
+    }
+
+    /// Shortest name without an `_` that's reported (default 4). Such names
+    /// must also be all-caps, like `PORT`; lower this to accept `DB` or `CI`.
+    /// Short all-caps words are common in ordinary strings, so expect more
+    /// false positives.
+    #[must_use]
+    pub fn min_word_length(mut self, len: usize) -> Self {
+        self.min_word_length = len;
+        self
+    }
+
+    /// Lowest address a string argument may be read from (default 1, so
+    /// only null pointers are skipped). Raise it to ignore pointers into the
+    /// first bytes of memory, where small integers that aren't pointers land.
+    #[must_use]
+    pub fn min_ptr(mut self, ptr: u32) -> Self {
+        self.min_ptr = ptr;
+        self
+    }
+
+    /// Longest string read from a call-site argument, in bytes (default 200).
+    /// Longer `(ptr, len)` pairs are assumed not to be names and aren't read,
+    /// so they don't show up among the rejected candidates either.
+    #[must_use]
+    pub fn max_string_len(mut self, len: u32) -> Self {
+        self.max_string_len = len;
+        self
+    }
+
+    /// Never report these names (compared case-insensitively), in addition
+    /// to the built-in noise list (`RUST_BACKTRACE`, `HOME`, ...). Useful for
+    /// strings that look like env vars in a particular codebase but aren't.
+    #[must_use]
+    pub fn extra_noise(mut self, words: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.extra_noise.extend(words.into_iter().map(Into::into));
+        self
+    }
+
+    /// Never report names matching any of these glob patterns, where `*`
+    /// matches any run of characters and `?` a single one (compared
+    /// case-insensitively, like [`Self::extra_noise`]). For recurring false
+    /// positives such as a codebase's internal constants: `INTERNAL_*`,
+    /// `*_SENTINEL`.
+    #[must_use]
+    pub fn ignore(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.ignore.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Never report names `regex` matches anywhere in; anchor it with `^`
+    /// and `$` to match whole names. Requires the `regex` feature.
+    #[cfg(feature = "regex")]
+    #[must_use]
+    pub fn ignore_regex(mut self, regex: regex::Regex) -> Self {
+        self.ignore_regex.push(regex);
+        self
+    }
+
+    /// Name segments that mark a config variable in a particular domain, in
+    /// addition to the built-in ones (`URL`, `PORT`, `REGION`, ...). A name
+    /// with one of these `_`-separated segments scores higher, which can
+    /// raise its [`crate::Confidence`].
+    #[must_use]
+    pub fn extra_keywords(mut self, segments: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.extra_keywords
+            .extend(segments.into_iter().map(Into::into));
+        self
+    }
+
+    /// Accept lowercase and mixed-case names, like `database_url` or
+    /// `my_api_token`, where only `SCREAMING_SNAKE_CASE` is otherwise
+    /// trusted: export names, data and debug strings, log messages and
+    /// `(len, ptr)` reads.
+    ///
+    /// Such a name still needs a `_` and must end in a keyword segment (a
+    /// built-in one such as `URL` or `TOKEN`, or one of
+    /// [`Self::extra_keywords`]), since plenty of ordinary identifiers are
+    /// lowercase with underscores. Names read from a traced `(ptr, len)` call site
+    /// are accepted in any case regardless.
+    #[must_use]
+    pub fn allow_lowercase(mut self, enabled: bool) -> Self {
+        self.allow_lowercase = enabled;
+        self
+    }
+
+    /// Accept dotted config keys like `app.database.url` at traced call
+    /// sites, for runtimes whose config store is keyed that way.
+    ///
+    /// Every `.`-separated part has to be a word (a letter, then letters and
+    /// `_`, then optionally a number), so version strings and hashes don't
+    /// pass, and a last part like `json` or `com` marks a file or host name,
+    /// which is rejected. `/` and `:` are never accepted, so paths and URLs
+    /// stay out.
+    #[must_use]
+    pub fn allow_dotted(mut self, enabled: bool) -> Self {
+        self.allow_dotted = enabled;
+        self
+    }
+
+    /// Accept dash-separated keys like `FEATURE-FLAG-X` at traced call sites,
+    /// with the same per-part shape check as [`Self::allow_dotted`]. UUIDs
+    /// are rejected that way, but lowercase HTTP header names such as
+    /// `content-type` look just like such keys.
+    #[must_use]
+    pub fn allow_dashed(mut self, enabled: bool) -> Self {
+        self.allow_dashed = enabled;
+        self
+    }
+
+    /// Treat names that differ only in case, like `DATABASE_URL` and
+    /// `Database_Url`, as one variable, reported under the spelling with the
+    /// most upper-case letters (ties go to the first in byte order).
+    ///
+    /// Every hit keeps its own location; only its name is replaced. Not
+    /// applied to [`crate::scan_with_callback`].
+    #[must_use]
+    pub fn dedup_case_insensitive(mut self, enabled: bool) -> Self {
+        self.dedup_case_insensitive = enabled;
+        self
+    }
+
+    /// Also report module export names that look like env vars.
+    ///
+    /// Some builds surface config keys only as export names. These hits carry
+    /// no data flow into an env API, so they're reported with
+    /// [`crate::Confidence::Low`], and only `SCREAMING_SNAKE_CASE` names are
+    /// considered since ordinary exports are lowercase identifiers.
+    #[must_use]
+    pub fn scan_exports(mut self, enabled: bool) -> Self {
+        self.scan_exports = enabled;
+        self
+    }
+
+    /// Recover names passed as a bare pointer into a NUL-separated strings
+    /// blob.
+    ///
+    /// Some frameworks intern every string into one blob and pass only an
+    /// offset where an env API expects `(ptr, len)`. With this enabled, call
+    /// sites where no name could be read as a `(ptr, len)` pair have their
+    /// known pointer arguments read up to the next NUL byte instead. Only
+    /// pointers at the start of a blob entry (just after a NUL) are read.
+    #[must_use]
+    pub fn interned_strings(mut self, enabled: bool) -> Self {
+        self.interned_strings = enabled;
+        self
+    }
+
+    /// Recover names passed as a single NUL-terminated pointer to a function
+    /// that reaches a `(ptr, len)` env API, e.g. a C wrapper that runs
+    /// `strlen` before calling into WASI.
+    ///
+    /// Only call sites where no `(ptr, len)` pair reads as a name, and with
+    /// exactly one known argument, are tried. Off by default, since in Rust
+    /// modules a lone known pointer is rarely a C string.
+    #[must_use]
+    pub fn c_strings(mut self, enabled: bool) -> Self {
+        self.c_strings = enabled;
+        self
+    }
+
+    /// Drop hits below `min` confidence (see [`crate::EnvVarHit::confidence`]),
+    /// e.g. [`Confidence::High`] to keep only names traced into an env import
+    /// that also look like typical variable names. Corroborated hits count as
+    /// `High`. The default, [`Confidence::Low`], keeps every hit.
+    #[must_use]
+    pub fn min_confidence(mut self, min: Confidence) -> Self {
+        self.min_confidence = min;
+        self
+    }
+
+    /// Only report names referenced from at least `min` distinct functions.
+    ///
+    /// Cuts one-off false positives, such as a stray string that happens to
+    /// reach an env call in a single function. Several call sites within the
+    /// same function count once. `0` and `1` (the default) keep every name.
+    #[must_use]
+    pub fn min_functions(mut self, min: usize) -> Self {
+        self.min_functions = min;
+        self
+    }
+
+    /// Also look for names mentioned in messages written with WASI
+    /// `fd_write`.
+    ///
+    /// Error output like `"DATABASE_URL is not set"` often names a variable
+    /// even when the read itself can't be traced. `fd_write` takes its
+    /// strings through an array of `(ptr, len)` iovecs in memory, so at calls
+    /// whose iovec pointer is a constant, each static iovec is read and
+    /// `SCREAMING_SNAKE_CASE` words in the text are reported with
+    /// [`crate::Confidence::Low`].
+    #[must_use]
+    pub fn scan_logs(mut self, enabled: bool) -> Self {
+        self.scan_logs = enabled;
+        self
+    }
+
+    /// Also report names found in the DWARF `.debug_str` section of
+    /// unstripped builds.
+    ///
+    /// Debug info records the identifiers of the source, so a constant or
+    /// field named after a variable shows up even when the read itself
+    /// couldn't be traced. Nothing ties these strings to the code that runs,
+    /// and debug info can describe source the optimizer dropped, so matches
+    /// may be stale; they're reported with [`crate::Confidence::Low`] as
+    /// [`crate::HitSource::DebugInfo`].
+    #[must_use]
+    pub fn debug_info(mut self, enabled: bool) -> Self {
+        self.debug_info = enabled;
+        self
+    }
+
+    /// Only report names found by at least two detection methods (see
+    /// [`crate::EnvVarHit::corroborated`]).
+    ///
+    /// Only useful together with a pass that finds names some other way, such
+    /// as [`ScanConfig::scan_logs`] or [`ScanConfig::scan_exports`]: with the
+    /// defaults every hit is a call site, so nothing is corroborated.
+    #[must_use]
+    pub fn corroborated_only(mut self, enabled: bool) -> Self {
+        self.corroborated_only = enabled;
+        self
+    }
+
+    /// Read a `(ptr, len)` string that runs into memory no data segment
+    /// initializes as its bytes up to the gap, instead of dropping it.
+    ///
+    /// Covers names whose length was mis-inferred as too long, or which sit
+    /// at the very end of the last segment. The prefix still has to pass the
+    /// usual name filters.
+    #[must_use]
+    pub fn partial_strings(mut self, enabled: bool) -> Self {
+        self.partial_strings = enabled;
+        self
+    }
+
+    /// Treat more imports as env reads, for hosts whose ABI the built-in
+    /// list doesn't know.
+    ///
+    /// `classify` is called with the `(module, name)` of every function
+    /// import. Imports it classifies as [`CalleeKind::Getenv`] or
+    /// [`CalleeKind::StringSink`] are traced exactly like the built-in
+    /// `env.getenv` and WASI imports. It adds to the built-in detection rather
+    /// than replacing it: imports it ignores are still recognized if they're
+    /// on the built-in list.
+    ///
+    /// # Example
+    /// ```
+    /// use wasm2env::{CalleeKind, ScanConfig};
+    ///
+    /// let config = ScanConfig::new().callee_classifier(|module, name| {
+    ///     match (module, name) {
+    ///         ("my-host", "config-get") => CalleeKind::StringSink,
+    ///         _ => CalleeKind::Ignore,
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn callee_classifier(
+        mut self,
+        classify: impl Fn(&str, &str) -> CalleeKind + Send + Sync + 'static,
+    ) -> Self {
+        self.callee_classifier = Some(CalleeClassifier(Arc::new(classify)));
+        self
+    }
+}
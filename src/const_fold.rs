@@ -0,0 +1,324 @@
+//! Folding of extended constant expressions.
+//!
+//! The extended-const proposal allows `i32.add`/`i32.sub`/`i32.mul` (and the
+//! i64 equivalents) in constant expressions, which newer LLVM uses for data
+//! segment offsets and global initializers. walrus only understands
+//! single-instruction constant expressions and rejects the whole module
+//! otherwise, so before handing a module to walrus we evaluate these
+//! expressions with a tiny interpreter and splice the result back in as a
+//! plain `i32.const`/`i64.const`.
+//!
+//! Expressions that can't be folded (e.g. they read an imported global) are
+//! downgraded so the module still loads: data segments become passive (and so
+//! drop out of the memory map) and globals keep only their `global.get`.
+
+use std::borrow::Cow;
+
+use wasmparser::{
+    BinaryReader, ConstExpr, DataKind, DataSectionReader, GlobalSectionReader, ImportSectionReader,
+    Operator, TypeRef,
+};
+
+const SECTION_IMPORT: u8 = 2;
+const SECTION_GLOBAL: u8 = 6;
+const SECTION_DATA: u8 = 11;
+
+/// A folded constant value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Konst {
+    I32(i32),
+    I64(i64),
+}
+
+/// Result of evaluating a constant expression.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Folded {
+    /// The expression was already a single instruction; leave it untouched.
+    Simple,
+    Const(Konst),
+    /// Depends on a global we can't resolve statically.
+    Unknown {
+        global_index: Option<u32>,
+    },
+}
+
+/// Fold extended constant expressions in a core module's global and data
+/// sections. Returns the input unchanged (borrowed) when there's nothing to
+/// fold or the module can't be walked.
+pub fn fold_extended_consts(module_bytes: &[u8]) -> Cow<'_, [u8]> {
+    match rewrite_module(module_bytes) {
+        Some(rewritten) => Cow::Owned(rewritten),
+        None => Cow::Borrowed(module_bytes),
+    }
+}
+
+fn rewrite_module(module_bytes: &[u8]) -> Option<Vec<u8>> {
+    // 4-byte magic + 4-byte version, then a sequence of (id, size, contents)
+    let mut reader = BinaryReader::new(module_bytes.get(8..)?);
+    let mut out = module_bytes[..8].to_vec();
+    let mut globals: Vec<Option<Konst>> = Vec::new();
+    let mut changed = false;
+
+    while !reader.eof() {
+        let section_start = 8 + reader.current_position();
+        let id = reader.read_u8().ok()?;
+        let size = reader.read_var_u32().ok()? as usize;
+        let contents_start = 8 + reader.current_position();
+        let contents = reader.read_bytes(size).ok()?;
+
+        let new_contents = match id {
+            SECTION_IMPORT => {
+                // Imported globals come first in the index space; their values
+                // are only known at instantiation time
+                let imports = ImportSectionReader::new(contents, contents_start).ok()?;
+                for import in imports {
+                    if let TypeRef::Global(_) = import.ok()?.ty {
+                        globals.push(None);
+                    }
+                }
+                None
+            }
+            SECTION_GLOBAL => Some(rewrite_globals(
+                module_bytes,
+                contents_start,
+                size,
+                &mut globals,
+            )?),
+            SECTION_DATA => Some(rewrite_data(module_bytes, contents_start, size, &globals)?),
+            _ => None,
+        };
+
+        match new_contents {
+            Some(new_contents) if new_contents != contents => {
+                changed = true;
+                out.push(id);
+                write_u32_leb(&mut out, new_contents.len() as u32);
+                out.extend_from_slice(&new_contents);
+            }
+            _ => out.extend_from_slice(&module_bytes[section_start..contents_start + size]),
+        }
+    }
+
+    changed.then_some(out)
+}
+
+/// Rewrite the contents of the global section, recording each global's
+/// folded value.
+fn rewrite_globals(
+    bytes: &[u8],
+    start: usize,
+    size: usize,
+    globals: &mut Vec<Option<Konst>>,
+) -> Option<Vec<u8>> {
+    let end = start + size;
+    let reader = GlobalSectionReader::new(&bytes[start..end], start).ok()?;
+
+    let mut out = Vec::new();
+    let mut cursor = start;
+
+    for item in reader.into_iter_with_offsets() {
+        let (_, global) = item.ok()?;
+        let expr = global.init_expr.get_binary_reader().range();
+
+        let folded = fold(&global.init_expr, globals)?;
+        globals.push(match folded {
+            Folded::Const(k) => Some(k),
+            Folded::Simple => simple_value(&global.init_expr, globals),
+            Folded::Unknown { .. } => None,
+        });
+
+        let replacement = match folded {
+            Folded::Const(k) => encode_const(k),
+            Folded::Unknown {
+                global_index: Some(index),
+            } => encode_global_get(index),
+            // An unknown value with no global behind it can't occur in a valid
+            // module; leave it for walrus to reject
+            Folded::Simple | Folded::Unknown { global_index: None } => continue,
+        };
+
+        out.extend_from_slice(&bytes[cursor..expr.start]);
+        out.extend_from_slice(&replacement);
+        cursor = expr.end;
+    }
+
+    out.extend_from_slice(&bytes[cursor..end]);
+    Some(out)
+}
+
+/// Rewrite the contents of the data section, folding active segment offsets.
+fn rewrite_data(
+    bytes: &[u8],
+    start: usize,
+    size: usize,
+    globals: &[Option<Konst>],
+) -> Option<Vec<u8>> {
+    let end = start + size;
+    let reader = DataSectionReader::new(&bytes[start..end], start).ok()?;
+
+    let mut out = Vec::new();
+    let mut cursor = start;
+
+    for item in reader {
+        let data = item.ok()?;
+        let DataKind::Active { offset_expr, .. } = data.kind else {
+            continue;
+        };
+        let expr = offset_expr.get_binary_reader().range();
+
+        match fold(&offset_expr, globals)? {
+            Folded::Simple => continue,
+            Folded::Const(k) => {
+                // Keep the flags/memory index, swap in the folded offset
+                out.extend_from_slice(&bytes[cursor..expr.start]);
+                out.extend_from_slice(&encode_const(k));
+            }
+            Folded::Unknown { .. } => {
+                // Passive (flag 1) segments carry no memory index or offset
+                out.extend_from_slice(&bytes[cursor..data.range.start]);
+                out.push(0x01);
+            }
+        }
+        cursor = expr.end;
+    }
+
+    out.extend_from_slice(&bytes[cursor..end]);
+    Some(out)
+}
+
+/// Evaluate a constant expression. Returns `None` if it can't be read.
+fn fold(expr: &ConstExpr, globals: &[Option<Konst>]) -> Option<Folded> {
+    let mut ops = expr.get_operators_reader();
+    let mut stack: Vec<Option<Konst>> = Vec::new();
+    let mut count = 0;
+    let mut first_global = None;
+
+    loop {
+        let op = ops.read().ok()?;
+        count += 1;
+        match op {
+            Operator::End => break,
+            Operator::I32Const { value } => stack.push(Some(Konst::I32(value))),
+            Operator::I64Const { value } => stack.push(Some(Konst::I64(value))),
+            Operator::GlobalGet { global_index } => {
+                first_global.get_or_insert(global_index);
+                stack.push(globals.get(global_index as usize).copied().flatten());
+            }
+            Operator::I32Add | Operator::I32Sub | Operator::I32Mul => {
+                let (rhs, lhs) = (stack.pop()?, stack.pop()?);
+                stack.push(match (lhs, rhs) {
+                    (Some(Konst::I32(a)), Some(Konst::I32(b))) => Some(Konst::I32(match op {
+                        Operator::I32Add => a.wrapping_add(b),
+                        Operator::I32Sub => a.wrapping_sub(b),
+                        _ => a.wrapping_mul(b),
+                    })),
+                    _ => None,
+                });
+            }
+            Operator::I64Add | Operator::I64Sub | Operator::I64Mul => {
+                let (rhs, lhs) = (stack.pop()?, stack.pop()?);
+                stack.push(match (lhs, rhs) {
+                    (Some(Konst::I64(a)), Some(Konst::I64(b))) => Some(Konst::I64(match op {
+                        Operator::I64Add => a.wrapping_add(b),
+                        Operator::I64Sub => a.wrapping_sub(b),
+                        _ => a.wrapping_mul(b),
+                    })),
+                    _ => None,
+                });
+            }
+            // Anything else (ref.func, f32.const, ...) is a single-instruction
+            // expression walrus already handles
+            _ => return Some(Folded::Simple),
+        }
+    }
+
+    // A single instruction plus `end` needs no folding
+    if count <= 2 {
+        return Some(Folded::Simple);
+    }
+    match stack.as_slice() {
+        [Some(k)] => Some(Folded::Const(*k)),
+        [None] => Some(Folded::Unknown {
+            global_index: first_global,
+        }),
+        _ => None,
+    }
+}
+
+/// Value of a single-instruction integer initializer, for later `global.get`s.
+fn simple_value(expr: &ConstExpr, globals: &[Option<Konst>]) -> Option<Konst> {
+    match expr.get_operators_reader().read().ok()? {
+        Operator::I32Const { value } => Some(Konst::I32(value)),
+        Operator::I64Const { value } => Some(Konst::I64(value)),
+        Operator::GlobalGet { global_index } => globals.get(global_index as usize).copied()?,
+        _ => None,
+    }
+}
+
+fn encode_const(k: Konst) -> Vec<u8> {
+    let mut out = Vec::new();
+    match k {
+        Konst::I32(v) => {
+            out.push(0x41);
+            write_i64_leb(&mut out, i64::from(v));
+        }
+        Konst::I64(v) => {
+            out.push(0x42);
+            write_i64_leb(&mut out, v);
+        }
+    }
+    out.push(0x0b);
+    out
+}
+
+fn encode_global_get(index: u32) -> Vec<u8> {
+    let mut out = vec![0x23];
+    write_u32_leb(&mut out, index);
+    out.push(0x0b);
+    out
+}
+
+pub(crate) fn write_u32_leb(out: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn write_i64_leb(out: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_leb() {
+        let mut out = Vec::new();
+        write_u32_leb(&mut out, 624_485);
+        assert_eq!(out, [0xe5, 0x8e, 0x26]);
+
+        let mut out = Vec::new();
+        write_i64_leb(&mut out, -123_456);
+        assert_eq!(out, [0xc0, 0xbb, 0x78]);
+
+        let mut out = Vec::new();
+        write_i64_leb(&mut out, 64);
+        assert_eq!(out, [0xc0, 0x00]);
+    }
+}
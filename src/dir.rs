@@ -0,0 +1,60 @@
+//! Directory input: every `.wasm` (or `.wasm.gz`) file under a directory.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use walkdir::WalkDir;
+
+/// Paths of the `.wasm` and `.wasm.gz` files under `dir`, recursively and in a stable
+/// order. Entries below `dir` that can't be read come back as errors in
+/// place, so one unreadable subdirectory doesn't hide the rest; only `dir`
+/// itself being unreadable fails the whole walk.
+pub fn wasm_files(dir: &str) -> Result<Vec<Result<PathBuf>>> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        match entry {
+            Ok(entry) => {
+                let name = entry.file_name().to_string_lossy();
+                let is_wasm = entry.file_type().is_file()
+                    && (name.ends_with(".wasm") || name.ends_with(".wasm.gz"));
+                if is_wasm {
+                    files.push(Ok(entry.into_path()));
+                }
+            }
+            Err(err) if err.depth() == 0 => {
+                return Err(err).with_context(|| format!("Failed to read {dir}"));
+            }
+            Err(err) => {
+                let path = err
+                    .path()
+                    .map_or_else(|| dir.to_string(), |path| path.display().to_string());
+                files.push(Err(err).with_context(|| format!("Failed to read {path}")));
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasm_files() {
+        let files: Vec<PathBuf> = wasm_files("test-components")
+            .unwrap()
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert!(files.contains(&PathBuf::from("test-components/multi-env.wasm")));
+        // Only modules, not the sources they're built from
+        assert!(files.iter().all(|path| path
+            .extension()
+            .is_some_and(|ext| ext == "wasm" || ext == "gz")));
+        let mut sorted = files.clone();
+        sorted.sort();
+        assert_eq!(files, sorted);
+
+        assert!(wasm_files("no/such/dir").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! Minimal `.env` file support for the CLI.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+
+/// Parse `.env` contents into `NAME → value`.
+///
+/// Supports `NAME=value` lines with an optional `export ` prefix, `#` comment
+/// lines and single- or double-quoted values. Lines without `=` are ignored.
+pub fn parse(contents: &str) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        vars.insert(name.trim().to_string(), unquote(value.trim()).to_string());
+    }
+    vars
+}
+
+/// Load variables from a `.env` file, or from the process environment when
+/// `path` is `-`.
+pub fn load(path: &str) -> Result<HashMap<String, String>> {
+    if path == "-" {
+        // Skip non-UTF-8 entries; they can't match a detected name anyway
+        return Ok(std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .collect());
+    }
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read env file: {path}"))?;
+    Ok(parse(&contents))
+}
+
+/// Write a `.env` skeleton for `env_vars` (sorted) to `path`: a comment
+/// naming `source`, then one `NAME=` line per variable, filled in with the
+/// variable's entry in `defaults` if it has one.
+///
+/// An existing file is merged into rather than overwritten: its lines are
+/// kept as they are and only names it doesn't assign yet are appended.
+/// Returns how many names were added.
+pub fn write_template(
+    path: &str,
+    source: &str,
+    env_vars: &[String],
+    defaults: &HashMap<&str, &str>,
+) -> Result<usize> {
+    let existing = match std::fs::read_to_string(path) {
+        Ok(contents) => Some(contents),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err).with_context(|| format!("Failed to read env file: {path}")),
+    };
+    let (contents, added) = template(existing.as_deref(), source, env_vars, defaults);
+    if added > 0 || existing.is_none() {
+        std::fs::write(path, contents)
+            .with_context(|| format!("Failed to write env file: {path}"))?;
+    }
+    Ok(added)
+}
+
+/// The new file contents and the number of names added; see
+/// [`write_template`].
+fn template(
+    existing: Option<&str>,
+    source: &str,
+    env_vars: &[String],
+    defaults: &HashMap<&str, &str>,
+) -> (String, usize) {
+    let assigned = existing.map(parse).unwrap_or_default();
+    let new: Vec<&String> = env_vars
+        .iter()
+        .filter(|name| !assigned.contains_key(*name))
+        .collect();
+
+    let mut contents = existing.unwrap_or_default().to_string();
+    if new.is_empty() {
+        return (contents, 0);
+    }
+    let header = if existing.is_some() {
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        format!("\n# Added from {source}")
+    } else {
+        format!("# Environment variables required by {source}")
+    };
+    let lines: Vec<String> = std::iter::once(header)
+        .chain(new.iter().map(|name| {
+            let default = defaults.get(name.as_str()).copied().unwrap_or_default();
+            format!("{name}={default}")
+        }))
+        .collect();
+    contents.push_str(&lines.join("\n"));
+    contents.push('\n');
+    (contents, new.len())
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let vars = parse(
+            "# comment\n\
+             DATABASE_URL=postgres://localhost\n\
+             export API_KEY=\"abc 123\"\n\
+             EMPTY=\n\
+             QUOTED_EMPTY=''\n\
+             not a var\n",
+        );
+
+        assert_eq!(vars["DATABASE_URL"], "postgres://localhost");
+        assert_eq!(vars["API_KEY"], "abc 123");
+        assert_eq!(vars["EMPTY"], "");
+        assert_eq!(vars["QUOTED_EMPTY"], "");
+        assert_eq!(vars.len(), 4);
+    }
+
+    #[test]
+    fn test_template() {
+        let vars = vec!["API_KEY".to_string(), "LOG_LEVEL".to_string()];
+        let defaults = HashMap::from([("LOG_LEVEL", "info")]);
+
+        let (fresh, added) = template(None, "app.wasm", &vars, &defaults);
+        assert_eq!(
+            fresh,
+            "# Environment variables required by app.wasm\nAPI_KEY=\nLOG_LEVEL=info\n"
+        );
+        assert_eq!(added, 2);
+
+        let (merged, added) = template(Some("API_KEY=abc"), "app.wasm", &vars, &defaults);
+        assert_eq!(
+            merged,
+            "API_KEY=abc\n\n# Added from app.wasm\nLOG_LEVEL=info\n"
+        );
+        assert_eq!(added, 1);
+
+        let (unchanged, added) = template(Some(&merged), "app.wasm", &vars, &defaults);
+        assert_eq!(unchanged, merged);
+        assert_eq!(added, 0);
+    }
+}
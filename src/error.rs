@@ -0,0 +1,48 @@
+//! The library's error type.
+
+use std::io;
+
+/// Why a scan failed.
+///
+/// Binaries that parse but can't be analyzed (e.g. a core module walrus
+/// rejects) are not errors; they're skipped and show up in
+/// [`crate::ScanStats`] instead.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Wasm2EnvError {
+    /// Reading the input (a file or stream) failed.
+    #[error("{context}")]
+    Io {
+        /// What was being read, e.g. `Failed to read WASM file: app.wasm`.
+        context: String,
+        #[source]
+        source: io::Error,
+    },
+    /// The input isn't a well-formed WASM binary: bad magic number, unknown
+    /// version, malformed section and so on.
+    #[error(transparent)]
+    Parse(#[from] wasmparser::BinaryReaderError),
+    /// The input ended early. The detailed scans return a best-effort report
+    /// with [`crate::ScanReport::truncated_at`] set instead.
+    #[error("module appears truncated at offset {offset:#x}")]
+    Truncated {
+        /// Where the input ended.
+        offset: usize,
+    },
+    /// WebAssembly text failed to parse or assemble.
+    #[cfg(feature = "wat")]
+    #[error("Failed to parse WAT")]
+    Wat(#[source] wat::Error),
+}
+
+impl Wasm2EnvError {
+    pub(crate) fn io(context: impl Into<String>, source: io::Error) -> Self {
+        Self::Io {
+            context: context.into(),
+            source,
+        }
+    }
+}
+
+/// `Result` with [`Wasm2EnvError`] as the default error.
+pub type Result<T, E = Wasm2EnvError> = std::result::Result<T, E>;
@@ -0,0 +1,253 @@
+//! Lowering of the (legacy) exception-handling proposal.
+//!
+//! walrus can't represent `try`/`catch`, and rejects any module with a tag
+//! section outright. Since we only care about the happy path through a
+//! function, we rewrite EH into plain MVP control flow before handing the
+//! module to walrus:
+//!
+//! - `try bt` becomes `block bt`, so the protected body is analyzed as usual
+//! - `catch`/`catch_all` handler code is dropped (only the closing `end` stays)
+//! - `delegate` becomes `end`
+//! - `throw`/`rethrow` become `unreachable`
+//! - tags (the tag section, tag imports and tag exports) are removed
+//!
+//! Function and global indices are unaffected, so hits still point at the
+//! original binary.
+
+use std::borrow::Cow;
+
+use wasmparser::{
+    BinaryReader, CodeSectionReader, ExportSectionReader, ExternalKind, ImportSectionReader,
+    Operator, Parser, Payload, TypeRef,
+};
+
+use crate::const_fold::write_u32_leb;
+
+const SECTION_IMPORT: u8 = 2;
+const SECTION_EXPORT: u8 = 7;
+const SECTION_CODE: u8 = 10;
+const SECTION_TAG: u8 = 13;
+
+const OP_UNREACHABLE: u8 = 0x00;
+const OP_BLOCK: u8 = 0x02;
+const OP_END: u8 = 0x0b;
+
+/// Lower exception handling in a core module. Returns the input unchanged
+/// (borrowed) when the module doesn't use it or can't be walked.
+pub fn lower_exceptions(module_bytes: &[u8]) -> Cow<'_, [u8]> {
+    if !uses_exceptions(module_bytes) {
+        return Cow::Borrowed(module_bytes);
+    }
+    match rewrite_module(module_bytes) {
+        Some(rewritten) => Cow::Owned(rewritten),
+        None => Cow::Borrowed(module_bytes),
+    }
+}
+
+/// Whether the module declares or imports a tag, or any function uses an EH
+/// instruction. `catch_all`, `delegate` and `rethrow` are valid without a
+/// tag, so the code has to be checked too; it's only read, not re-encoded.
+fn uses_exceptions(module_bytes: &[u8]) -> bool {
+    for payload in Parser::new(0).parse_all(module_bytes) {
+        let found = match payload {
+            Ok(Payload::TagSection(_)) => true,
+            Ok(Payload::ImportSection(imports)) => imports
+                .into_iter()
+                .any(|import| matches!(import, Ok(import) if matches!(import.ty, TypeRef::Tag(_)))),
+            Ok(Payload::CodeSectionEntry(body)) => body.get_operators_reader().is_ok_and(|ops| {
+                ops.into_iter().any(|op| {
+                    matches!(
+                        op,
+                        Ok(Operator::Try { .. }
+                            | Operator::Catch { .. }
+                            | Operator::CatchAll
+                            | Operator::Delegate { .. }
+                            | Operator::Throw { .. }
+                            | Operator::Rethrow { .. })
+                    )
+                })
+            }),
+            // Left for walrus to report
+            Err(_) => return false,
+            Ok(_) => false,
+        };
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
+fn rewrite_module(module_bytes: &[u8]) -> Option<Vec<u8>> {
+    // magic + version, then (id, size, contents)
+    let mut reader = BinaryReader::new_with_offset(module_bytes.get(8..)?, 8);
+    let mut out = module_bytes[..8].to_vec();
+
+    while !reader.eof() {
+        let section_start = reader.original_position();
+        let id = reader.read_u8().ok()?;
+        let size = reader.read_var_u32().ok()? as usize;
+        let contents_start = reader.original_position();
+        let contents = reader.read_bytes(size).ok()?;
+
+        let new_contents = match id {
+            SECTION_TAG => continue,
+            SECTION_IMPORT => {
+                let imports = ImportSectionReader::new(contents, contents_start).ok()?;
+                let (kept, removed) = filter_items(contents_start, contents, imports, |import| {
+                    !matches!(import.ty, TypeRef::Tag(_))
+                })?;
+                removed.then_some(kept)
+            }
+            SECTION_EXPORT => {
+                let exports = ExportSectionReader::new(contents, contents_start).ok()?;
+                let (kept, removed) = filter_items(contents_start, contents, exports, |export| {
+                    export.kind != ExternalKind::Tag
+                })?;
+                removed.then_some(kept)
+            }
+            SECTION_CODE => Some(lower_code_section(contents, contents_start)?),
+            _ => None,
+        };
+
+        match new_contents {
+            Some(new_contents) => {
+                out.push(id);
+                write_u32_leb(&mut out, new_contents.len() as u32);
+                out.extend_from_slice(&new_contents);
+            }
+            None => out.extend_from_slice(&module_bytes[section_start..contents_start + size]),
+        }
+    }
+
+    Some(out)
+}
+
+/// Re-encode a vector section keeping only the items `keep` accepts. Also
+/// returns whether any item was removed.
+fn filter_items<'a, T>(
+    start: usize,
+    contents: &'a [u8],
+    reader: wasmparser::SectionLimited<'a, T>,
+    keep: impl Fn(&T) -> bool,
+) -> Option<(Vec<u8>, bool)>
+where
+    T: wasmparser::FromReader<'a>,
+{
+    let end = start + contents.len();
+    let mut items: Vec<(usize, bool)> = Vec::new();
+    for item in reader.into_iter_with_offsets() {
+        let (offset, item) = item.ok()?;
+        items.push((offset, keep(&item)));
+    }
+
+    let mut body = Vec::new();
+    let mut count = 0;
+    let mut removed = false;
+    for (i, &(offset, kept)) in items.iter().enumerate() {
+        let item_end = items.get(i + 1).map_or(end, |(next, _)| *next);
+        if kept {
+            body.extend_from_slice(&contents[offset - start..item_end - start]);
+            count += 1;
+        } else {
+            removed = true;
+        }
+    }
+
+    let mut out = Vec::new();
+    write_u32_leb(&mut out, count);
+    out.extend_from_slice(&body);
+    Some((out, removed))
+}
+
+fn lower_code_section(contents: &[u8], start: usize) -> Option<Vec<u8>> {
+    let bodies = CodeSectionReader::new(contents, start).ok()?;
+    let mut out = Vec::new();
+    write_u32_leb(&mut out, bodies.count());
+
+    for body in bodies {
+        let body = body.ok()?;
+        let range = body.range();
+        let ops = body.get_operators_reader().ok()?;
+        let ops_start = ops.original_position();
+
+        // Local declarations are copied unchanged
+        let mut lowered = contents[range.start - start..ops_start - start].to_vec();
+        lower_operators(contents, start, range.end, ops, &mut lowered)?;
+
+        write_u32_leb(&mut out, lowered.len() as u32);
+        out.extend_from_slice(&lowered);
+    }
+    Some(out)
+}
+
+/// Copy a function's operators into `out`, lowering EH instructions.
+fn lower_operators(
+    contents: &[u8],
+    start: usize,
+    end: usize,
+    ops: wasmparser::OperatorsReader,
+    out: &mut Vec<u8>,
+) -> Option<()> {
+    // Number of open blocks, and the depth of the `try` whose handler code is
+    // currently being dropped
+    let mut depth = 0usize;
+    let mut skipping: Option<usize> = None;
+
+    let mut ops = ops.into_iter_with_offsets().peekable();
+    while let Some(item) = ops.next() {
+        let (op, offset) = item.ok()?;
+        let next = match ops.peek() {
+            Some(Ok((_, next))) => *next,
+            Some(Err(_)) => return None,
+            None => end,
+        };
+        let bytes = &contents[offset - start..next - start];
+
+        match op {
+            Operator::Block { .. } | Operator::Loop { .. } | Operator::If { .. } => depth += 1,
+            Operator::Try { .. } => {
+                depth += 1;
+                if skipping.is_none() {
+                    // Same block type immediate, different opcode
+                    out.push(OP_BLOCK);
+                    out.extend_from_slice(&bytes[1..]);
+                }
+                continue;
+            }
+            Operator::Catch { .. } | Operator::CatchAll => {
+                skipping.get_or_insert(depth);
+                continue;
+            }
+            Operator::Delegate { .. } => {
+                // Ends a `try` that has no handlers
+                depth = depth.saturating_sub(1);
+                if skipping.is_none() {
+                    out.push(OP_END);
+                }
+                continue;
+            }
+            Operator::End => {
+                let closing = depth;
+                depth = depth.saturating_sub(1);
+                if skipping == Some(closing) {
+                    skipping = None;
+                    out.push(OP_END);
+                    continue;
+                }
+            }
+            Operator::Throw { .. } | Operator::Rethrow { .. } => {
+                if skipping.is_none() {
+                    out.push(OP_UNREACHABLE);
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        if skipping.is_none() {
+            out.extend_from_slice(bytes);
+        }
+    }
+    Some(())
+}
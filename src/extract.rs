@@ -4,33 +4,329 @@
 //! This module uses `wasmparser` to locate and extract those core modules so
 //! they can be individually analyzed by the taint-analysis pass.
 
-use anyhow::Result;
-use wasmparser::{Parser, Payload};
+use std::io::Read;
+
+use wasmparser::{
+    BinaryReaderError, Chunk, ComponentImportSectionReader, DataSectionReader, Parser, Payload,
+};
+
+use crate::error::{Result, Wasm2EnvError};
+
+/// How much to read from a stream at a time when the parser doesn't ask for
+/// more.
+const READ_CHUNK: u64 = 64 * 1024;
+
+/// Core modules found in a binary.
+pub struct CoreModules {
+    pub modules: Vec<CoreModule>,
+    /// Offset at which the input ended unexpectedly, if it was truncated.
+    /// `modules` then only holds the modules that were complete before it.
+    pub truncated_at: Option<usize>,
+    /// `wasi:cli/environment` interfaces the outermost component imports,
+    /// e.g. `wasi:cli/environment@0.2.0`.
+    pub environment_imports: Vec<String>,
+}
+
+/// A core module's bytes, copied out of the binary.
+pub struct CoreModule {
+    pub bytes: Vec<u8>,
+    /// Offset in the binary of each data segment's bytes, indexed like the
+    /// data section. Stops at the first segment that can't be read; the
+    /// offsets are only used to point users at the bytes of a name.
+    pub data_offsets: Vec<usize>,
+}
 
 /// Extract core WASM modules from a component binary.
 /// If the input is already a core module, returns it as-is.
-pub fn extract_core_modules(wasm_bytes: &[u8]) -> Result<Vec<Vec<u8>>> {
-    let parser = Parser::new(0);
+///
+/// Only section headers are parsed here: embedded modules and a core
+/// module's code section are skipped over whole, since walrus parses them
+/// again anyway during analysis.
+///
+/// A truncated input (e.g. a partial download) is not an error: parsing stops
+/// at the unexpected end-of-file and the modules seen so far are returned.
+pub fn extract_core_modules(wasm_bytes: &[u8]) -> Result<CoreModules> {
+    let mut parser = Parser::new(0);
+    // Parsers of the enclosing components while inside a nested one
+    let mut parents = Vec::new();
+    let mut data = wasm_bytes;
     let mut modules = Vec::new();
     let mut is_core_module = false;
+    let mut truncated_at = None;
+    let mut environment_imports = Vec::new();
+    // Of the input itself, if it's a core module
+    let mut data_offsets = Vec::new();
+
+    loop {
+        let payload = match parser.parse(data, true) {
+            Ok(Chunk::Parsed { consumed, payload }) => {
+                data = &data[consumed..];
+                payload
+            }
+            // Not possible with `eof` set
+            Ok(Chunk::NeedMoreData(_)) => unreachable!(),
+            Err(e) if is_unexpected_eof(&e) => {
+                truncated_at = Some(e.offset());
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        match payload {
+            Payload::Version {
+                encoding: wasmparser::Encoding::Module,
+                ..
+            } => {
+                is_core_module = true;
+            }
+            Payload::CodeSectionStart { size, .. } => {
+                // A truncated section is parsed entry by entry instead, to
+                // find where it ends
+                if let Some(rest) = data.get(size as usize..) {
+                    parser.skip_section();
+                    data = rest;
+                }
+            }
+            Payload::DataSection(reader) => push_data_offsets(reader, &mut data_offsets),
+            Payload::ModuleSection {
+                parser: nested,
+                range,
+            } => {
+                // The section header is yielded before its body is parsed, so
+                // a truncated module can claim a range past the end of input
+                let Some(module) = wasm_bytes.get(range) else {
+                    truncated_at = Some(wasm_bytes.len());
+                    break;
+                };
+                modules.push(CoreModule {
+                    bytes: module.to_vec(),
+                    data_offsets: module_data_offsets(nested, module),
+                });
+                data = &data[module.len()..];
+            }
+            Payload::ComponentSection { parser: nested, .. } => {
+                parents.push(std::mem::replace(&mut parser, nested));
+            }
+            // A nested component's imports are wired up by its parent
+            Payload::ComponentImportSection(reader) if parents.is_empty() => {
+                environment_imports.extend(environment_interfaces(reader)?);
+            }
+            Payload::End(_) => match parents.pop() {
+                Some(parent) => parser = parent,
+                None => break,
+            },
+            _ => {}
+        }
+    }
+
+    if is_core_module && modules.is_empty() && truncated_at.is_none() {
+        modules.push(CoreModule {
+            bytes: wasm_bytes.to_vec(),
+            data_offsets,
+        });
+    }
+
+    Ok(CoreModules {
+        modules,
+        truncated_at,
+        environment_imports,
+    })
+}
+
+/// [`extract_core_modules`] on a stream, without holding the whole binary in
+/// memory: each embedded core module is copied straight from the reader, and
+/// only the component-level sections between them are buffered. A plain core
+/// module is read in full, since it's all needed for analysis anyway.
+pub fn read_core_modules(mut reader: impl Read) -> Result<CoreModules> {
+    // Unconsumed input, starting at stream offset `offset`
+    let mut buf = Vec::new();
+    let mut offset = 0;
+    let mut eof = false;
+    let mut parser = Parser::new(0);
+    // Parsers of the enclosing components while inside a nested one
+    let mut parents = Vec::new();
+    let mut modules = Vec::new();
+    let mut environment_imports = Vec::new();
+
+    loop {
+        let (consumed, next) = match parser.parse(&buf, eof) {
+            Ok(Chunk::NeedMoreData(hint)) => {
+                eof = fill(&mut reader, &mut buf, hint.max(READ_CHUNK))? == 0;
+                continue;
+            }
+            Ok(Chunk::Parsed { consumed, payload }) => (consumed, Next::of(payload)?),
+            Err(e) if is_unexpected_eof(&e) => {
+                return Ok(CoreModules {
+                    modules,
+                    truncated_at: Some(e.offset()),
+                    environment_imports,
+                });
+            }
+            Err(e) => return Err(e.into()),
+        };
 
-    for payload in parser.parse_all(wasm_bytes) {
-        match payload? {
-            Payload::Version { encoding, .. } => {
-                if encoding == wasmparser::Encoding::Module {
-                    is_core_module = true;
+        match next {
+            Next::CoreModule if parents.is_empty() => {
+                reader
+                    .read_to_end(&mut buf)
+                    .map_err(|e| Wasm2EnvError::io("Failed to read WASM module", e))?;
+                return extract_core_modules(&buf);
+            }
+            Next::CoreModule | Next::Section => {}
+            Next::Module(len) => {
+                buf.drain(..consumed);
+                offset += consumed;
+                let missing = len.saturating_sub(buf.len());
+                if missing > 0 {
+                    fill(&mut reader, &mut buf, missing as u64)?;
+                }
+                if buf.len() < len {
+                    return Ok(CoreModules {
+                        modules,
+                        truncated_at: Some(offset + buf.len()),
+                        environment_imports,
+                    });
+                }
+                let bytes: Vec<u8> = buf.drain(..len).collect();
+                modules.push(CoreModule {
+                    data_offsets: module_data_offsets(Parser::new(offset as u64), &bytes),
+                    bytes,
+                });
+                offset += len;
+                continue;
+            }
+            Next::Component(nested) => parents.push(std::mem::replace(&mut parser, nested)),
+            Next::Imports(names) => {
+                if parents.is_empty() {
+                    environment_imports.extend(names);
                 }
             }
-            Payload::ModuleSection { range, .. } => {
-                modules.push(wasm_bytes[range.start..range.end].to_vec());
+            Next::End => match parents.pop() {
+                Some(parent) => parser = parent,
+                None => break,
+            },
+        }
+        buf.drain(..consumed);
+        offset += consumed;
+    }
+
+    Ok(CoreModules {
+        modules,
+        truncated_at: None,
+        environment_imports,
+    })
+}
+
+/// What [`read_core_modules`] does after a payload, kept apart from the
+/// payload itself since that borrows the input buffer.
+enum Next {
+    /// The input is a core module rather than a component.
+    CoreModule,
+    /// A core module of this many bytes follows.
+    Module(usize),
+    /// A nested component follows, to be parsed with this parser.
+    Component(Parser),
+    /// A component import section importing these environment interfaces.
+    Imports(Vec<String>),
+    End,
+    Section,
+}
+
+impl Next {
+    fn of(payload: Payload) -> Result<Self> {
+        Ok(match payload {
+            Payload::Version {
+                encoding: wasmparser::Encoding::Module,
+                ..
+            } => Next::CoreModule,
+            Payload::ModuleSection { range, .. } => Next::Module(range.len()),
+            Payload::ComponentSection { parser, .. } => Next::Component(parser),
+            Payload::ComponentImportSection(reader) => {
+                Next::Imports(environment_interfaces(reader)?)
+            }
+            Payload::End(_) => Next::End,
+            _ => Next::Section,
+        })
+    }
+}
+
+/// [`CoreModule::data_offsets`] of an embedded module, `parser` being the
+/// one its module section came with. Like [`extract_core_modules`], this
+/// only reads section headers and skips the code section whole.
+fn module_data_offsets(mut parser: Parser, mut module: &[u8]) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    while let Ok(Chunk::Parsed { consumed, payload }) = parser.parse(module, true) {
+        module = &module[consumed..];
+        match payload {
+            Payload::CodeSectionStart { size, .. } => {
+                let Some(rest) = module.get(size as usize..) else {
+                    break;
+                };
+                parser.skip_section();
+                module = rest;
             }
+            Payload::DataSection(reader) => push_data_offsets(reader, &mut offsets),
+            Payload::End(_) => break,
             _ => {}
         }
     }
+    offsets
+}
 
-    if is_core_module && modules.is_empty() {
-        return Ok(vec![wasm_bytes.to_vec()]);
+/// Append the offset of each data segment's bytes, up to the first one that
+/// can't be read.
+fn push_data_offsets(reader: DataSectionReader, offsets: &mut Vec<usize>) {
+    for data in reader {
+        let Ok(data) = data else {
+            return;
+        };
+        // The bytes end the entry
+        offsets.push(data.range.end - data.data.len());
     }
+}
+
+/// Names of the `wasi:cli/environment` interfaces in a component import
+/// section, any version.
+fn environment_interfaces(reader: ComponentImportSectionReader) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for import in reader {
+        let name = import?.name.0;
+        if name == "wasi:cli/environment" || name.starts_with("wasi:cli/environment@") {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
 
-    Ok(modules)
+/// Append up to `max` more bytes from `reader` to `buf`, returning how many
+/// were read (0 at the end of the stream).
+fn fill(reader: &mut impl Read, buf: &mut Vec<u8>, max: u64) -> Result<usize> {
+    reader
+        .take(max)
+        .read_to_end(buf)
+        .map_err(|e| Wasm2EnvError::io("Failed to read WASM binary", e))
+}
+
+/// Whether a parse error means the input simply ran out of bytes.
+fn is_unexpected_eof(err: &BinaryReaderError) -> bool {
+    err.message().ends_with("unexpected end-of-file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_offsets_match_when_streamed() {
+        let bytes = std::fs::read("test-components/multi-env.wasm").unwrap();
+        let extracted = extract_core_modules(&bytes).unwrap();
+        let streamed = read_core_modules(bytes.as_slice()).unwrap();
+        assert_eq!(extracted.modules.len(), streamed.modules.len());
+        for (a, b) in extracted.modules.iter().zip(&streamed.modules) {
+            assert_eq!(a.data_offsets, b.data_offsets);
+        }
+        assert!(extracted
+            .modules
+            .iter()
+            .any(|module| !module.data_offsets.is_empty()));
+    }
 }
@@ -0,0 +1,38 @@
+//! Downloading modules for `--url` (requires the `net` feature).
+
+use std::io::Read;
+
+use anyhow::{bail, Context, Result};
+
+/// Refuse downloads larger than this, so a wrong URL can't exhaust memory.
+const MAX_DOWNLOAD_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Fetch `url` into memory, following redirects. Returns the final URL
+/// alongside the body.
+pub fn fetch(url: &str) -> Result<(String, Vec<u8>)> {
+    let response = ureq::get(url)
+        .call()
+        .with_context(|| format!("Failed to fetch {url}"))?;
+    let final_url = response.get_url().to_string();
+
+    if let Some(len) = response
+        .header("Content-Length")
+        .and_then(|len| len.parse::<u64>().ok())
+    {
+        if len > MAX_DOWNLOAD_BYTES {
+            bail!("{final_url} is {len} bytes, over the {MAX_DOWNLOAD_BYTES}-byte download limit");
+        }
+    }
+
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to download {final_url}"))?;
+    if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
+        bail!("{final_url} exceeds the {MAX_DOWNLOAD_BYTES}-byte download limit");
+    }
+
+    Ok((final_url, bytes))
+}
@@ -0,0 +1,129 @@
+//! C ABI for embedders that aren't Rust (C, Go via cgo, Zig, ...).
+//!
+//! The declarations are in `include/wasm2env.h`; link against the `cdylib`
+//! or `staticlib` the crate builds.
+
+use std::ffi::{c_char, CString};
+use std::panic;
+use std::ptr;
+
+/// The scan succeeded and `*out` holds the names.
+const OK: i32 = 0;
+/// `out` or `out_len` was null, or `ptr` was null with a nonzero `len`.
+const NULL_ARGUMENT: i32 = 1;
+/// The bytes couldn't be scanned (malformed or truncated module).
+const SCAN_FAILED: i32 = 2;
+
+/// Scans the `len` bytes at `ptr` like [`crate::scan_wasm_bytes`].
+///
+/// On success, returns 0 and stores the sorted variable names, joined with
+/// `\n` and NUL-terminated, in `*out`, and their length in bytes (not
+/// counting the NUL) in `*out_len`. No variables gives an empty string,
+/// which still has to be freed.
+///
+/// On error, returns 1 for a null argument or 2 if the scan failed, and sets
+/// `*out` to null and `*out_len` to 0 (when those pointers aren't null).
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes; it may be null only when `len`
+/// is 0. `out` and `out_len` must be valid for writes. The buffer stored in
+/// `*out` belongs to the caller, who must release it with exactly one call
+/// to [`wasm2env_free`] (not the C allocator's `free`).
+#[no_mangle]
+pub unsafe extern "C" fn wasm2env_scan(
+    ptr: *const u8,
+    len: usize,
+    out: *mut *mut c_char,
+    out_len: *mut usize,
+) -> i32 {
+    if out.is_null() || out_len.is_null() {
+        return NULL_ARGUMENT;
+    }
+    unsafe {
+        *out = ptr::null_mut();
+        *out_len = 0;
+    }
+    if ptr.is_null() && len > 0 {
+        return NULL_ARGUMENT;
+    }
+    let bytes = if len == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(ptr, len) }
+    };
+
+    // Unwinding into C is undefined, so a panic in the analysis is reported
+    // like any other failed scan
+    let Ok(Ok(names)) = panic::catch_unwind(|| crate::scan_wasm_bytes(bytes)) else {
+        return SCAN_FAILED;
+    };
+    // Names are validated identifiers and never contain a NUL
+    let Ok(joined) = CString::new(names.join("\n")) else {
+        return SCAN_FAILED;
+    };
+    unsafe {
+        *out_len = joined.as_bytes().len();
+        *out = joined.into_raw();
+    }
+    OK
+}
+
+/// Releases a buffer returned by [`wasm2env_scan`]. Null is ignored.
+///
+/// # Safety
+///
+/// `buf` must be null or a pointer stored by [`wasm2env_scan`] that hasn't
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn wasm2env_free(buf: *mut c_char) {
+    if !buf.is_null() {
+        drop(unsafe { CString::from_raw(buf) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(bytes: &[u8]) -> (i32, Option<String>) {
+        let mut out = ptr::null_mut();
+        let mut out_len = 0;
+        let code =
+            unsafe { wasm2env_scan(bytes.as_ptr(), bytes.len(), &raw mut out, &raw mut out_len) };
+        if out.is_null() {
+            assert_eq!(out_len, 0);
+            return (code, None);
+        }
+        let names = unsafe { std::slice::from_raw_parts(out.cast::<u8>(), out_len) };
+        let names = String::from_utf8(names.to_vec()).unwrap();
+        unsafe { wasm2env_free(out) };
+        (code, Some(names))
+    }
+
+    #[test]
+    fn test_scan() {
+        let bytes = std::fs::read("test-components/multi-env.wasm").unwrap();
+        assert_eq!(
+            scan(&bytes),
+            (OK, Some("API_KEY\nDATABASE_URL\nJWT_SECRET".to_string()))
+        );
+        let bytes = std::fs::read("test-components/no-env.wasm").unwrap();
+        assert_eq!(scan(&bytes), (OK, Some(String::new())));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(scan(b"not wasm"), (SCAN_FAILED, None));
+
+        let mut out = ptr::null_mut();
+        let mut out_len = 0;
+        let code = unsafe { wasm2env_scan(ptr::null(), 8, &raw mut out, &raw mut out_len) };
+        assert_eq!(code, NULL_ARGUMENT);
+        assert!(out.is_null());
+        let code = unsafe { wasm2env_scan(ptr::null(), 0, ptr::null_mut(), &raw mut out_len) };
+        assert_eq!(code, NULL_ARGUMENT);
+
+        unsafe { wasm2env_free(ptr::null_mut()) };
+    }
+}
@@ -0,0 +1,35 @@
+//! Reading a module's committed version for `--since-git`.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::Repository;
+
+/// Contents of the file at `path` as of the git revision `rev` (anything
+/// `git rev-parse` accepts, e.g. `main` or `HEAD~1`), read from the
+/// repository containing `path`.
+pub fn read_at_rev(path: &str, rev: &str) -> Result<Vec<u8>> {
+    let path = Path::new(path)
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {path}"))?;
+    let repo = Repository::discover(&path)
+        .with_context(|| format!("{} is not inside a git repository", path.display()))?;
+    let workdir = repo
+        .workdir()
+        .context("--since-git doesn't work in a bare repository")?
+        .canonicalize()?;
+    let relative = path
+        .strip_prefix(&workdir)
+        .with_context(|| format!("{} is outside the repository", path.display()))?;
+
+    let tree = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_tree())
+        .with_context(|| format!("Unknown git revision '{rev}'"))?;
+    let blob = tree
+        .get_path(relative)
+        .and_then(|entry| entry.to_object(&repo))
+        .and_then(|object| object.peel_to_blob())
+        .with_context(|| format!("{} doesn't exist at {rev}", relative.display()))?;
+    Ok(blob.content().to_vec())
+}
@@ -0,0 +1,52 @@
+//! Decompressing gzip-compressed modules (requires the `gzip` feature).
+
+use std::io::Read;
+
+use anyhow::{bail, Context, Result};
+use flate2::read::MultiGzDecoder;
+
+/// Refuse to inflate past this, so a small archive can't exhaust memory.
+const MAX_DECOMPRESSED_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Decompress a gzip stream (concatenated members are joined, as `gunzip`
+/// does).
+pub fn decompress(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    MultiGzDecoder::new(bytes)
+        .take(MAX_DECOMPRESSED_BYTES + 1)
+        .read_to_end(&mut out)
+        .context("Failed to decompress gzip input")?;
+    if out.len() as u64 > MAX_DECOMPRESSED_BYTES {
+        bail!("gzip input inflates past the {MAX_DECOMPRESSED_BYTES}-byte limit");
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_decompress() {
+        let wasm = std::fs::read("test-components/multi-env.wasm").unwrap();
+        assert_eq!(decompress(&gzip(&wasm)).unwrap(), wasm);
+
+        // Concatenated members, as from `cat a.gz b.gz`
+        let mut joined = gzip(b"\0asm");
+        joined.extend(gzip(b"\x01\0\0\0"));
+        assert_eq!(decompress(&joined).unwrap(), b"\0asm\x01\0\0\0");
+
+        assert!(decompress(&[0x1f, 0x8b, 0x08]).is_err());
+    }
+}
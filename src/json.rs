@@ -0,0 +1,111 @@
+//! Streaming JSON output for batch scans.
+
+use std::io::Write;
+use std::path::Path;
+
+use crate::{scan_wasm_file, Result, Wasm2EnvError};
+
+/// Scans each file in turn and writes the results to `writer` as one JSON
+/// array, one element per file:
+///
+/// ```json
+/// [{"file":"a.wasm","env_vars":["API_KEY"]},{"file":"b.wasm","error":"..."}]
+/// ```
+///
+/// Each element is written and flushed as soon as its file is scanned, so
+/// memory use stays flat however many files there are. A file that fails to
+/// scan gets an `error` element instead of ending the batch; only write
+/// errors are returned.
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::write_json_stream;
+///
+/// let files = ["a.wasm", "b.wasm"];
+/// write_json_stream(std::io::stdout().lock(), &files).unwrap();
+/// ```
+pub fn write_json_stream<W, P>(writer: W, files: impl IntoIterator<Item = P>) -> Result<()>
+where
+    W: Write,
+    P: AsRef<Path>,
+{
+    write_stream(writer, files).map_err(|e| Wasm2EnvError::io("Failed to write JSON stream", e))
+}
+
+fn write_stream<W, P>(mut writer: W, files: impl IntoIterator<Item = P>) -> std::io::Result<()>
+where
+    W: Write,
+    P: AsRef<Path>,
+{
+    writer.write_all(b"[")?;
+    for (i, path) in files.into_iter().enumerate() {
+        let path = path.as_ref();
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        writer.write_all(b"{\"file\":")?;
+        write_json_string(&mut writer, &path.to_string_lossy())?;
+        match scan_wasm_file(path) {
+            Ok(env_vars) => {
+                writer.write_all(b",\"env_vars\":[")?;
+                for (j, name) in env_vars.iter().enumerate() {
+                    if j > 0 {
+                        writer.write_all(b",")?;
+                    }
+                    write_json_string(&mut writer, name)?;
+                }
+                writer.write_all(b"]")?;
+            }
+            Err(e) => {
+                writer.write_all(b",\"error\":")?;
+                write_json_string(&mut writer, &error_chain(&e))?;
+            }
+        }
+        writer.write_all(b"}")?;
+        writer.flush()?;
+    }
+    writer.write_all(b"]\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// `err` followed by each of its causes, separated by `: `.
+fn error_chain(err: &Wasm2EnvError) -> String {
+    let mut message = err.to_string();
+    let mut source = std::error::Error::source(err);
+    while let Some(cause) = source {
+        message = format!("{message}: {cause}");
+        source = cause.source();
+    }
+    message
+}
+
+/// Write `s` as a quoted JSON string, escaping quotes, backslashes and
+/// control characters. Shared with the CLI's JSON and SARIF output.
+pub fn write_json_string(writer: &mut impl Write, s: &str) -> std::io::Result<()> {
+    writer.write_all(b"\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => writer.write_all(b"\\\"")?,
+            '\\' => writer.write_all(b"\\\\")?,
+            '\n' => writer.write_all(b"\\n")?,
+            '\r' => writer.write_all(b"\\r")?,
+            '\t' => writer.write_all(b"\\t")?,
+            c if c < ' ' => write!(writer, "\\u{:04x}", c as u32)?,
+            c => write!(writer, "{c}")?,
+        }
+    }
+    writer.write_all(b"\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_json_string() {
+        let mut out = Vec::new();
+        write_json_string(&mut out, "a \"b\"\\c\n\u{1}").unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), r#""a \"b\"\\c\n\u0001""#);
+    }
+}
@@ -17,15 +17,45 @@
 //!    Strings are validated and filtered ([`strings`]) to produce the final list.
 
 mod analysis;
+mod cache;
+mod category;
+mod config;
+mod const_fold;
+mod error;
+mod exceptions;
 mod extract;
+mod ffi;
+mod json;
+mod memory;
+mod report;
+mod schema;
+mod score;
 mod stack;
 mod strings;
+mod stub;
 
-use anyhow::{Context, Result};
+use std::collections::HashSet;
 use std::fs;
+use std::io::Read;
 use std::path::Path;
 
-use analysis::detect_env_vars;
+use analysis::{
+    collect_call_strings, detect_env_vars, detect_in_core_modules, detect_in_module, for_each_hit,
+};
+use extract::{extract_core_modules, read_core_modules};
+use memory::module_memories;
+use report::flag_suspicious_overlaps;
+
+pub use cache::CachingScanner;
+pub use category::Category;
+pub use config::{CalleeKind, ScanConfig};
+pub use error::{Result, Wasm2EnvError};
+pub use json::{write_json_stream, write_json_string};
+pub use memory::ModuleMemory;
+pub use report::{
+    Confidence, EnvVarHit, HitSource, NameRule, ScanReport, ScanStats, Severity, SkippedFunction,
+};
+pub use schema::SchemaDiff;
 
 /// Scans a WASM binary file for environment variable dependencies.
 ///
@@ -46,21 +76,59 @@ use analysis::detect_env_vars;
 /// ```
 pub fn scan_wasm_file<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
     let path_ref = path.as_ref();
-    let data = fs::read(path_ref)
-        .with_context(|| format!("Failed to read WASM file: {}", path_ref.display()))?;
+    let data = fs::read(path_ref).map_err(|e| {
+        Wasm2EnvError::io(
+            format!("Failed to read WASM file: {}", path_ref.display()),
+            e,
+        )
+    })?;
 
     scan_wasm_bytes(&data)
 }
 
+/// Scans a WASM binary read from a stream, e.g. a file too large to load
+/// into memory in one go.
+///
+/// For a component, each embedded core module is read into memory in turn
+/// (analysis needs a whole module at once) but the component as a whole
+/// never is. A plain core module is read in full. Results are the same as
+/// [`scan_wasm_bytes`], including truncated input being an error.
+///
+/// # Example
+/// ```no_run
+/// use std::fs::File;
+/// use std::io::BufReader;
+/// use wasm2env::scan_wasm_reader;
+///
+/// let file = File::open("./my-component.wasm").unwrap();
+/// let env_vars = scan_wasm_reader(BufReader::new(file)).unwrap();
+/// ```
+pub fn scan_wasm_reader<R: Read>(reader: R) -> Result<Vec<String>> {
+    let report = detect_in_core_modules(&read_core_modules(reader)?, &ScanConfig::default());
+
+    if let Some(offset) = report.truncated_at {
+        return Err(Wasm2EnvError::Truncated { offset });
+    }
+
+    Ok(report.names())
+}
+
 /// Scans WASM binary bytes for environment variable dependencies.
 ///
-/// This is the recommended interface for FFI usage (e.g., from Elixir via Rustler).
+/// This is the recommended interface for FFI usage (e.g., from Elixir via Rustler);
+/// embedders without a Rust toolchain can call it through the C function
+/// `wasm2env_scan` declared in `include/wasm2env.h`.
+/// The names are [`scan_wasm_bytes_detailed`]'s hits, de-duplicated and
+/// sorted; use that to see where each one was found (function index, data
+/// address, [`HitSource`]).
 ///
 /// # Arguments
 /// * `wasm_bytes` - Raw WASM binary data
 ///
 /// # Returns
-/// * `Result<Vec<String>>` - Sorted list of detected environment variable names
+/// * `Result<Vec<String>>` - Sorted list of detected environment variable names.
+///   Truncated input is an error here; use [`scan_wasm_bytes_detailed`] for a
+///   best-effort result.
 ///
 /// # Example
 /// ```no_run
@@ -73,12 +141,356 @@ pub fn scan_wasm_file<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
 /// }
 /// ```
 pub fn scan_wasm_bytes(wasm_bytes: &[u8]) -> Result<Vec<String>> {
-    let env_vars = detect_env_vars(wasm_bytes)?;
+    scan_wasm_bytes_with_config(wasm_bytes, &ScanConfig::default())
+}
 
-    let mut result: Vec<String> = env_vars.into_iter().collect();
-    result.sort();
+/// Like [`scan_wasm_bytes`], with the scan controlled by a [`ScanConfig`].
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::{scan_wasm_bytes_with_config, ScanConfig};
+///
+/// let wasm_data = std::fs::read("./my-component.wasm").unwrap();
+/// let config = ScanConfig::new().scan_exports(true);
+/// let env_vars = scan_wasm_bytes_with_config(&wasm_data, &config).unwrap();
+/// ```
+pub fn scan_wasm_bytes_with_config(wasm_bytes: &[u8], config: &ScanConfig) -> Result<Vec<String>> {
+    let report = detect_env_vars(wasm_bytes, config)?;
 
-    Ok(result)
+    if let Some(offset) = report.truncated_at {
+        return Err(Wasm2EnvError::Truncated { offset });
+    }
+
+    Ok(report.names())
+}
+
+/// Scans a WebAssembly text (`.wat`) file for environment variable
+/// dependencies. Requires the `wat` feature.
+///
+/// The text is assembled to a binary first; see [`scan_wat_bytes`].
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::scan_wat_file;
+///
+/// let env_vars = scan_wat_file("./my-module.wat").unwrap();
+/// ```
+#[cfg(feature = "wat")]
+pub fn scan_wat_file<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
+    let path_ref = path.as_ref();
+    let data = fs::read(path_ref).map_err(|e| {
+        Wasm2EnvError::io(
+            format!("Failed to read WAT file: {}", path_ref.display()),
+            e,
+        )
+    })?;
+
+    scan_wat_bytes(&data)
+}
+
+/// Assembles WebAssembly text to a binary and scans it like
+/// [`scan_wasm_bytes`]. Requires the `wat` feature.
+///
+/// A syntax error in the text fails with [`Wasm2EnvError::Wat`]; text that
+/// assembles but doesn't parse as a module fails like a malformed binary,
+/// with [`Wasm2EnvError::Parse`]. Input that is already a binary is scanned
+/// as is.
+///
+/// # Example
+/// ```
+/// use wasm2env::scan_wat_bytes;
+///
+/// let env_vars = scan_wat_bytes(b"(module)").unwrap();
+/// assert!(env_vars.is_empty());
+/// assert!(scan_wat_bytes(b"(module").is_err());
+/// ```
+#[cfg(feature = "wat")]
+pub fn scan_wat_bytes(wat_bytes: &[u8]) -> Result<Vec<String>> {
+    let wasm_bytes = wat::parse_bytes(wat_bytes).map_err(Wasm2EnvError::Wat)?;
+
+    scan_wasm_bytes(&wasm_bytes)
+}
+
+/// Scans WASM binary bytes and returns a [`ScanReport`] with one [`EnvVarHit`]
+/// per call site.
+///
+/// Unlike [`scan_wasm_bytes`], names are not de-duplicated: the same variable
+/// read from several functions produces several hits. Hits reading the same
+/// pointer with different lengths are marked `suspicious_overlap`.
+///
+/// Hits are sorted by core module index, then `function_index`, then `ptr`
+/// (the byte offset of the name in linear memory), then name; hits with no
+/// function or pointer come first within their group. The order is part of the
+/// API: scanning the same bytes always yields the same `hits` in the same
+/// order, so reports can be snapshot-tested as-is.
+///
+/// Truncated input is scanned best-effort: hits from the core modules that
+/// were complete are returned and `truncated_at` records where the input ended.
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::scan_wasm_bytes_detailed;
+///
+/// let wasm_data = std::fs::read("./my-component.wasm").unwrap();
+/// let report = scan_wasm_bytes_detailed(&wasm_data).unwrap();
+/// for hit in &report.hits {
+///     println!("{} (function {:?})", hit.name, hit.function_index);
+/// }
+/// ```
+pub fn scan_wasm_bytes_detailed(wasm_bytes: &[u8]) -> Result<ScanReport> {
+    scan_wasm_bytes_detailed_with_config(wasm_bytes, &ScanConfig::default())
+}
+
+/// Like [`scan_wasm_bytes_detailed`], with the scan controlled by a [`ScanConfig`].
+pub fn scan_wasm_bytes_detailed_with_config(
+    wasm_bytes: &[u8],
+    config: &ScanConfig,
+) -> Result<ScanReport> {
+    let mut report = detect_env_vars(wasm_bytes, config)?;
+    flag_suspicious_overlaps(&mut report.hits);
+    Ok(report)
+}
+
+/// Scans WASM binary bytes, returning the hits along with [`ScanStats`]: what
+/// had to be left out, and counters of what the call-site pass looked at
+/// (functions walked, env call sites, strings read and rejected).
+///
+/// Meant for tuning and for answering "why wasn't `X` found": a string that
+/// was read but rejected shows up in `rejected_candidates` with the reason,
+/// while an `X` that was never read points at the call site not being
+/// traced. Truncated input is an error here; see
+/// [`scan_wasm_bytes_detailed`].
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::scan_with_stats;
+///
+/// let wasm_data = std::fs::read("./my-component.wasm").unwrap();
+/// let (hits, stats) = scan_with_stats(&wasm_data).unwrap();
+/// println!(
+///     "{} hits from {} strings read at {} call sites ({} rejected)",
+///     hits.len(),
+///     stats.strings_read,
+///     stats.env_call_sites,
+///     stats.strings_rejected
+/// );
+/// ```
+pub fn scan_with_stats(wasm_bytes: &[u8]) -> Result<(Vec<EnvVarHit>, ScanStats)> {
+    let report = scan_wasm_bytes_detailed(wasm_bytes)?;
+    if let Some(offset) = report.truncated_at {
+        return Err(Wasm2EnvError::Truncated { offset });
+    }
+    Ok((report.hits, report.stats))
+}
+
+/// Scans WASM binary bytes, calling `on_hit` for each detection as it's found.
+///
+/// Useful for UIs that render detections live instead of waiting for the whole
+/// scan. Hits are delivered as each function is analyzed, before any sorting
+/// or de-duplication, so **the same variable may be delivered more than once**;
+/// aggregate on the caller's side as needed. `suspicious_overlap` and
+/// `corroborated` are always `false` here since they need the complete set of
+/// hits.
+///
+/// If the input is truncated, hits from the complete modules are delivered
+/// and then an error is returned.
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::scan_with_callback;
+///
+/// let wasm_data = std::fs::read("./my-component.wasm").unwrap();
+/// scan_with_callback(&wasm_data, &mut |hit| println!("found {}", hit.name)).unwrap();
+/// ```
+pub fn scan_with_callback(wasm_bytes: &[u8], on_hit: &mut dyn FnMut(EnvVarHit)) -> Result<()> {
+    let mut stats = ScanStats::default();
+    if let Some(offset) = for_each_hit(wasm_bytes, &ScanConfig::default(), &mut stats, on_hit)? {
+        return Err(Wasm2EnvError::Truncated { offset });
+    }
+    Ok(())
+}
+
+/// Wraps a [`scan_with_callback`] handler so it only sees the first hit for
+/// each name.
+///
+/// Later hits for an already-delivered name (another call site, function or
+/// module) are dropped, so the handler sees each variable once, in the order
+/// they were first found.
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::{first_per_name, scan_with_callback};
+///
+/// let wasm_data = std::fs::read("./my-component.wasm").unwrap();
+/// let mut on_hit = first_per_name(|hit| println!("found {}", hit.name));
+/// scan_with_callback(&wasm_data, &mut on_hit).unwrap();
+/// ```
+pub fn first_per_name(mut on_hit: impl FnMut(EnvVarHit)) -> impl FnMut(EnvVarHit) {
+    let mut seen = HashSet::new();
+    move |hit| {
+        if seen.insert(hit.name.clone()) {
+            on_hit(hit);
+        }
+    }
+}
+
+/// Scans a core module that has already been parsed with `walrus` 0.22, the
+/// version this crate is built against.
+///
+/// For embedders that parse the wasm themselves for other reasons: this runs
+/// only the detection core (call graph, stack simulation, string extraction)
+/// without re-parsing. Hits are reported as `module_index` 0. The
+/// compatibility passes of the byte-level APIs (extended-const folding,
+/// exception-handling lowering, skipping unsupported functions) happen before
+/// parsing, so they don't apply here.
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::{scan_walrus_module, ScanConfig};
+///
+/// let module = walrus::Module::from_file("./module.wasm").unwrap();
+/// let report = scan_walrus_module(&module, &ScanConfig::default());
+/// println!("{:?}", report.names());
+/// ```
+pub fn scan_walrus_module(module: &walrus::Module, config: &ScanConfig) -> ScanReport {
+    let mut report = detect_in_module(module, config);
+    flag_suspicious_overlaps(&mut report.hits);
+    report
+}
+
+/// Scans WASM binary bytes and compares the detected variables with the
+/// `expected` ones, e.g. the set a platform declares it provides.
+///
+/// Meant for CI contracts between app and platform teams: assert
+/// [`SchemaDiff::is_exact_match`], or inspect the two `missing_*` lists.
+/// Truncated input is an error, as with [`scan_wasm_bytes`].
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::scan_against;
+///
+/// let wasm_data = std::fs::read("./my-component.wasm").unwrap();
+/// let expected = vec!["DATABASE_URL".to_string(), "API_KEY".to_string()];
+/// let diff = scan_against(&wasm_data, &expected).unwrap();
+/// assert!(diff.is_exact_match(), "undeclared: {:?}", diff.missing_from_schema);
+/// ```
+pub fn scan_against(wasm_bytes: &[u8], expected: &[String]) -> Result<SchemaDiff> {
+    let detected = scan_wasm_bytes(wasm_bytes)?;
+    Ok(SchemaDiff::new(&detected, expected))
+}
+
+/// Strings read from env call arguments that were rejected as names, each
+/// with the reason, as `(candidate, reason)` sorted by candidate.
+///
+/// The diagnostic for "why didn't my variable show up?": if the name is
+/// listed here, it was found but filtered out; if it's missing, the call site
+/// or its arguments couldn't be traced. Same as
+/// `scan_wasm_bytes_detailed(bytes)?.stats.rejected_candidates`.
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::scan_rejections;
+///
+/// let wasm_data = std::fs::read("./my-component.wasm").unwrap();
+/// for (candidate, reason) in scan_rejections(&wasm_data).unwrap() {
+///     println!("{candidate}: {reason}");
+/// }
+/// ```
+pub fn scan_rejections(wasm_bytes: &[u8]) -> Result<Vec<(String, String)>> {
+    Ok(scan_wasm_bytes_detailed(wasm_bytes)?
+        .stats
+        .rejected_candidates)
+}
+
+/// Every string passed to any call as a `(ptr, len)` pair, sorted and
+/// de-duplicated: log messages, URLs, keys, as well as env var names.
+///
+/// Uses the same stack simulation and memory map as detection, but doesn't
+/// require the call to reach an env import or the string to look like a
+/// variable name. Useful for auditing what a component talks about; expect
+/// noise, since any two adjacent integer arguments that happen to point at
+/// valid UTF-8 are reported.
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::scan_all_strings;
+///
+/// let wasm_data = std::fs::read("./my-component.wasm").unwrap();
+/// for string in scan_all_strings(&wasm_data).unwrap() {
+///     println!("{string:?}");
+/// }
+/// ```
+pub fn scan_all_strings(wasm_bytes: &[u8]) -> Result<Vec<String>> {
+    collect_call_strings(wasm_bytes, &ScanConfig::default())
+}
+
+/// The initial linear memory of each core module: active data segments,
+/// constant globals and a reader over the combined bytes.
+///
+/// This is the memory map detection reads names from, exposed for running
+/// other passes over it. A component yields one [`ModuleMemory`] per core
+/// module, tagged with the same `module_index` as hits; a plain core module
+/// yields one. Truncated input is an error.
+///
+/// # Example
+/// ```no_run
+/// use wasm2env::extract_module_memory;
+///
+/// let wasm_data = std::fs::read("./my-component.wasm").unwrap();
+/// for memory in extract_module_memory(&wasm_data).unwrap() {
+///     for (addr, bytes) in &memory.data_segments {
+///         println!("module {}: {} bytes at {addr:#x}", memory.module_index, bytes.len());
+///     }
+///     println!("{:?}", memory.read_string(1024, 12));
+/// }
+/// ```
+pub fn extract_module_memory(wasm_bytes: &[u8]) -> Result<Vec<ModuleMemory>> {
+    let core_modules = extract_core_modules(wasm_bytes)?;
+    if let Some(offset) = core_modules.truncated_at {
+        return Err(Wasm2EnvError::Truncated { offset });
+    }
+    Ok(module_memories(&core_modules))
+}
+
+/// Whether `name` would be reported if it were found as a string argument at
+/// an env call site, i.e. whether it passes the name filters (syntax
+/// heuristics and the built-in noise list).
+///
+/// Meant for tooling that previews filtering, or that runs the same heuristic
+/// over candidate strings found some other way. The length thresholds and
+/// extra noise words configured on `config` apply.
+///
+/// A name is accepted when it:
+/// - is made only of ASCII letters, digits and `_` (anything else, including
+///   non-ASCII letters, rejects it), plus `.` and `-` where
+///   [`ScanConfig::allow_dotted`] and [`ScanConfig::allow_dashed`] allow them,
+/// - has at least one letter and doesn't start or end with `_`,
+/// - contains an `_`, or is an all-caps word of at least
+///   [`ScanConfig::min_word_length`] characters,
+/// - is within [`ScanConfig::min_length`] and [`ScanConfig::max_length`]
+///   bytes and isn't a noise word,
+/// - matches none of the [`ScanConfig::ignore`] patterns (nor, with the
+///   `regex` feature, the `ScanConfig::ignore_regex` ones),
+/// - with the `demangle` feature, isn't a mangled Rust symbol such as
+///   `_ZN4core3fmt9Formatter3pad17h0123456789abcdefE`.
+///
+/// [`scan_rejections`] gives the reason a detected string failed.
+///
+/// # Example
+/// ```
+/// use wasm2env::{would_detect, ScanConfig};
+///
+/// let config = ScanConfig::default();
+/// assert!(would_detect("DATABASE_URL", &config));
+/// assert!(would_detect("PORT", &config));
+/// assert!(!would_detect("RUST_BACKTRACE", &config));
+/// assert!(!would_detect("DÉBUT_URL", &config));
+///
+/// let config = ScanConfig::new().ignore(["INTERNAL_*"]);
+/// assert!(!would_detect("INTERNAL_URL", &config));
+/// ```
+pub fn would_detect(name: &str, config: &ScanConfig) -> bool {
+    strings::is_valid_env_name(name, config)
 }
 
 #[cfg(test)]
@@ -96,4 +508,22 @@ mod tests {
         let result = scan_wasm_bytes(&minimal_wasm).unwrap();
         assert_eq!(result, Vec::<String>::new());
     }
+
+    #[test]
+    fn test_scan_wasm_bytes_huge_local_count() {
+        // One function declaring u32::MAX i32 locals — must be rejected up
+        // front rather than allocated or iterated
+        let huge_locals_wasm = vec![
+            0x00, 0x61, 0x73, 0x6d, // WASM magic number
+            0x01, 0x00, 0x00, 0x00, // Version
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // Type section: () -> ()
+            0x03, 0x02, 0x01, 0x00, // Function section: one function of type 0
+            0x0a, 0x0a, 0x01, 0x08, // Code section: one 8-byte body
+            0x01, 0xff, 0xff, 0xff, 0xff, 0x0f, 0x7f, // u32::MAX locals of i32
+            0x0b, // end
+        ];
+
+        let result = scan_wasm_bytes(&huge_locals_wasm).unwrap();
+        assert_eq!(result, Vec::<String>::new());
+    }
 }
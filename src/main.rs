@@ -1,35 +1,556 @@
-use anyhow::Result;
-use wasm2env::scan_wasm_file;
+mod cli;
+#[cfg(feature = "dir")]
+mod dir;
+mod dotenv;
+#[cfg(feature = "net")]
+mod fetch;
+#[cfg(feature = "git")]
+mod git;
+#[cfg(feature = "gzip")]
+mod gzip;
+mod render;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "watch")]
+mod watch;
 
-fn main() -> Result<()> {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Usage: wasm2env <wasm-file>");
-        eprintln!();
-        eprintln!("Detects environment variables by analyzing WASM bytecode.");
-        return Ok(());
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{bail, Context, Result};
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
+use wasm2env::{scan_wasm_bytes_detailed_with_config, ScanConfig, ScanReport, SchemaDiff};
+
+use cli::{CheckEnvArgs, Cli, Command, DiffArgs, Input, ScanArgs, StringsArgs, ValidateArgs};
+use render::{render, render_diff, Format, RenderOptions};
+
+/// Read when there's no `--config`.
+const DEFAULT_CONFIG_FILE: &str = "wasm2env.toml";
+
+fn main() -> Result<ExitCode> {
+    let matches =
+        Cli::command().get_matches_from(cli::with_default_command(std::env::args_os().collect()));
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    apply_config_file(&mut cli, &matches)?;
+    match cli.command {
+        Command::Scan(args) => run_scan(&args),
+        Command::Diff(args) => run_diff(&args),
+        Command::Validate(args) => run_validate(&args),
+        Command::Strings(args) => run_strings(&args),
+        Command::CheckEnv(args) => run_check_env(&args),
+    }
+}
+
+fn run_scan(args: &ScanArgs) -> Result<ExitCode> {
+    let config = args.scan.config();
+    let options = args.render_options();
+
+    if args.watch {
+        let path = args
+            .input
+            .file
+            .as_deref()
+            .context("--watch requires a file")?;
+        watch_and_print(path, &config, &options, args.stats)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(dir) = args.input.file.as_deref().filter(|f| Path::new(f).is_dir()) {
+        return run_scan_dir(dir, args);
+    }
+
+    let (path, wasm_bytes) = read_input(&args.input)?;
+    let report = scan(&wasm_bytes, &config)?;
+
+    if args.interactive {
+        browse(&path, &report)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(env_path) = &args.check_env {
+        return check_env_file(env_path, &report.names());
     }
 
-    let path = &args[1];
+    print_report(&path, &report, &options, args.stats)?;
+    finish_scan(args, &path, &report)
+}
+
+/// Scan every `.wasm` file under `dir`. Files that fail to scan are listed at
+/// the end (and make the exit status 1) instead of stopping the walk.
+fn run_scan_dir(dir: &str, args: &ScanArgs) -> Result<ExitCode> {
+    if args.interactive {
+        bail!("--interactive takes a single file, not a directory");
+    }
+    let config = args.scan.config();
+    let options = args.render_options();
+
+    let files = wasm_files(dir)?;
+    let file_count = files.len();
+    let mut reports = Vec::new();
+    let mut failures = Vec::new();
+    for file in files {
+        let result = file.and_then(|file| {
+            let path = file.display().to_string();
+            let wasm_bytes = read_wasm_file(&path)?;
+            let report =
+                scan(&wasm_bytes, &config).with_context(|| format!("Failed to scan {path}"))?;
+            Ok((path, report))
+        });
+        match result {
+            Ok(scanned) => reports.push(scanned),
+            Err(err) => failures.push(err),
+        }
+    }
+
+    let mut union = ScanReport::default();
+    for (_, report) in &reports {
+        union.hits.extend(report.hits.iter().cloned());
+        // Module indices are per file, but whether any are set is what counts
+        union
+            .stats
+            .whole_environment_modules
+            .extend(&report.stats.whole_environment_modules);
+        union
+            .stats
+            .environment_imports
+            .extend(report.stats.environment_imports.iter().cloned());
+    }
+    let status = if let Some(env_path) = &args.check_env {
+        check_env_file(env_path, &union.names())?
+    } else {
+        if args.per_file {
+            for (path, report) in &reports {
+                print_report(path, report, &options, args.stats)?;
+            }
+        } else {
+            print_report(dir, &union, &options, args.stats)?;
+        }
+        finish_scan(args, dir, &union)?
+    };
+    if failures.is_empty() {
+        return Ok(status);
+    }
+    eprintln!(
+        "\nFailed to scan {} of {file_count} file(s):\n",
+        failures.len()
+    );
+    for err in &failures {
+        eprintln!("  {err:#}");
+    }
+    Ok(ExitCode::FAILURE)
+}
+
+/// The `--emit-dotenv`, `--require` and `--fail-on-detect` steps that follow
+/// a printed report.
+fn finish_scan(args: &ScanArgs, path: &str, report: &ScanReport) -> Result<ExitCode> {
+    let names = report.names();
+    if let Some(dotenv_path) = &args.emit_dotenv {
+        let added = dotenv::write_template(dotenv_path, path, &names, &report.defaults())?;
+        eprintln!("Added {added} variable(s) to {dotenv_path}.");
+    }
+    if let Some(require_path) = &args.require {
+        return check_required(require_path, &names);
+    }
+    if args.fail_on_detect && !names.is_empty() {
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// CI gate for `--require`: fail if a detected variable isn't in the list of
+/// variables the deployment provides. The report is already on stdout, so
+/// the missing names go to stderr.
+fn check_required(require_path: &str, env_vars: &[String]) -> Result<ExitCode> {
+    let contents = std::fs::read_to_string(require_path)
+        .with_context(|| format!("Failed to read {require_path}"))?;
+    let provided = declared_names(&contents);
+    let missing: Vec<&String> = env_vars
+        .iter()
+        .filter(|var| !provided.contains(var))
+        .collect();
+    if missing.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    eprintln!("Not provided by {require_path} ({}):\n", missing.len());
+    for var_name in missing {
+        eprintln!("  {var_name}");
+    }
+    Ok(ExitCode::FAILURE)
+}
+
+/// Print the variables added and removed between two versions of a module.
+fn run_diff(args: &DiffArgs) -> Result<ExitCode> {
+    let config = args.scan.config();
+    let (path, baseline_name, baseline_bytes) = match (&args.since_git, args.files.as_slice()) {
+        (Some(rev), [path]) => (path, rev, decompress(read_at_git_rev(path, rev)?)?),
+        (None, [before, after]) => (after, before, read_wasm_file(before)?),
+        (Some(_), _) => bail!("With --since-git, pass only the module to compare"),
+        (None, _) => bail!("Pass the old and the new module (or one module and --since-git)"),
+    };
+    let wasm_bytes = read_wasm_file(path)?;
+
+    let baseline = scan_wasm_bytes_detailed_with_config(&baseline_bytes, &config)
+        .with_context(|| format!("Failed to scan {path} at {baseline_name}"))?;
+    let report = scan(&wasm_bytes, &config)?;
+    render_diff(
+        &mut std::io::stdout().lock(),
+        path,
+        baseline_name,
+        &baseline.names(),
+        &report.names(),
+    )?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Contract check: fail unless the module reads exactly the declared
+/// variables.
+fn run_validate(args: &ValidateArgs) -> Result<ExitCode> {
+    let contents = std::fs::read_to_string(&args.expected)
+        .with_context(|| format!("Failed to read {}", args.expected))?;
+    let expected = declared_names(&contents);
+
+    let (path, wasm_bytes) = read_input(&args.input)?;
+    let report = scan(&wasm_bytes, &args.scan.config())?;
+    let diff = SchemaDiff::new(&report.names(), &expected);
+
+    if diff.is_exact_match() {
+        println!(
+            "{path} reads exactly the {} variables declared in {}.",
+            diff.satisfied.len(),
+            args.expected
+        );
+        return Ok(ExitCode::SUCCESS);
+    }
+    if !diff.missing_from_schema.is_empty() {
+        println!(
+            "Read by {path} but not declared ({}):\n",
+            diff.missing_from_schema.len()
+        );
+        for var_name in &diff.missing_from_schema {
+            println!("  {var_name}");
+        }
+        println!();
+    }
+    if !diff.missing_from_module.is_empty() {
+        println!(
+            "Declared but not read by {path} ({}):\n",
+            diff.missing_from_module.len()
+        );
+        for var_name in &diff.missing_from_module {
+            println!("  {var_name}");
+        }
+    }
+    Ok(ExitCode::FAILURE)
+}
+
+/// Variable names declared in a list file: one per line, ignoring blank
+/// lines, `#` comments and anything from `=` on.
+fn declared_names(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            line.split_once('=')
+                .map_or(line, |(name, _)| name)
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Every string passed to an env API, with the reason for each one that was
+/// rejected as a name.
+fn run_strings(args: &StringsArgs) -> Result<ExitCode> {
+    let (path, wasm_bytes) = read_input(&args.input)?;
+    let report = scan(&wasm_bytes, &args.scan.config())?;
+
+    let mut strings: Vec<(String, Option<&str>)> = report
+        .names()
+        .into_iter()
+        .map(|name| (name, None))
+        .collect();
+    strings.extend(
+        report
+            .stats
+            .rejected_candidates
+            .iter()
+            .map(|(candidate, reason)| (candidate.clone(), Some(reason.as_str()))),
+    );
+    strings.sort();
+
+    println!("Strings passed to env APIs in {path}:\n");
+    for (string, rejection) in strings {
+        match rejection {
+            None => println!("  {string}"),
+            Some(reason) => println!("  {string:?}  (rejected: {reason})"),
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Read the module from a file, stdin (`-`, or no file with stdin piped
+/// in) or URL, returning the name to show for it.
+fn read_input(input: &Input) -> Result<(String, Vec<u8>)> {
+    match (input.file.as_deref(), &input.url) {
+        (Some("-"), _) => read_stdin(),
+        (Some(path), _) => Ok((path.to_string(), read_wasm_file(path)?)),
+        (None, Some(url)) => {
+            let (url, bytes) = fetch_url(url)?;
+            Ok((url, decompress(bytes)?))
+        }
+        // Nothing piped in (e.g. stdin is `/dev/null` in CI) is a missing
+        // argument, not an empty module
+        (None, None) if !std::io::stdin().is_terminal() => match read_stdin()? {
+            (_, bytes) if bytes.is_empty() => bail!("Pass a WASM file, `-` for stdin, or --url"),
+            piped => Ok(piped),
+        },
+        (None, None) => bail!("Pass a WASM file, `-` for stdin, or --url"),
+    }
+}
 
-    println!("Analyzing WASM module for environment dependencies...");
-    println!("File: {path}");
-    println!("---------------------------------------------------\n");
+/// Read the whole of stdin as a module, decompressing it if it's gzipped.
+fn read_stdin() -> Result<(String, Vec<u8>)> {
+    let mut bytes = Vec::new();
+    std::io::stdin()
+        .lock()
+        .read_to_end(&mut bytes)
+        .context("Failed to read WASM module from stdin")?;
+    Ok(("<stdin>".to_string(), decompress(bytes)?))
+}
 
-    let env_vars = scan_wasm_file(path)?;
+/// Read a module from disk, decompressing it if it's gzipped.
+fn read_wasm_file(path: &str) -> Result<Vec<u8>> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read WASM file: {path}"))?;
+    decompress(bytes).with_context(|| format!("Failed to read WASM file: {path}"))
+}
 
-    if env_vars.is_empty() {
-        println!("No environment variable dependencies detected.");
+/// Inflate gzip input, recognized by its magic bytes so `.wasm.gz` files and
+/// compressed streams on stdin both work. A WASM binary starts with `\0asm`
+/// instead and is returned as is.
+fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>> {
+    const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+    if bytes.starts_with(&GZIP_MAGIC) {
+        gunzip(&bytes)
     } else {
-        println!("Required Environment Variables ({}):\n", env_vars.len());
+        Ok(bytes)
+    }
+}
+
+/// Reprint the report every time the file at `path` changes.
+fn watch_and_print(
+    path: &str,
+    config: &ScanConfig,
+    options: &RenderOptions,
+    stats: bool,
+) -> Result<()> {
+    watch_file(path, |path| {
+        // Clear the screen so only the latest report is visible
+        print!("\x1b[2J\x1b[H");
+        let result = read_wasm_file(path)
+            .and_then(|wasm_bytes| scan(&wasm_bytes, config))
+            .and_then(|report| print_report(path, &report, options, stats));
+        // A half-written module is expected mid-build; wait for the next change
+        if let Err(err) = result {
+            eprintln!("Error: {err:#}");
+        }
+    })
+}
+
+/// Scan a module, printing warnings about anything that limited the analysis
+/// to stderr.
+fn scan(wasm_bytes: &[u8], config: &ScanConfig) -> Result<ScanReport> {
+    let report = scan_wasm_bytes_detailed_with_config(wasm_bytes, config)?;
+
+    if let Some(offset) = report.truncated_at {
+        eprintln!(
+            "Warning: module appears truncated at offset {offset:#x}; results are best-effort.\n"
+        );
+    }
+    let unresolved = report.stats.unresolved_env_reads.len();
+    if unresolved > 0 {
+        eprintln!(
+            "Warning: {unresolved} function(s) read an env var whose name couldn't be determined statically.\n"
+        );
+    }
+    let relocatable = report.stats.relocatable_modules.len();
+    if relocatable > 0 {
+        eprintln!(
+            "Warning: {relocatable} module(s) are relocatable object files; reporting every env-like string in their data (low confidence).\n"
+        );
+    }
+    if report.hits.is_empty() && report.reads_whole_environment() {
+        eprintln!(
+            "Warning: no variable names found, but the module reads the whole environment (environ_get / get-environment); it may look variables up at runtime.\n"
+        );
+    }
+    let skipped = report.stats.skipped_functions.len();
+    if skipped > 0 {
+        eprintln!(
+            "Warning: skipped {skipped} function(s) using unsupported wasm features; results may be incomplete.\n"
+        );
+    }
+    Ok(report)
+}
 
-        for (i, var_name) in env_vars.iter().enumerate() {
-            println!("  {}. {}", i + 1, var_name);
+/// Print the report in the requested format.
+fn print_report(
+    path: &str,
+    report: &ScanReport,
+    options: &RenderOptions,
+    stats: bool,
+) -> Result<()> {
+    render(&mut std::io::stdout().lock(), options, path, report)?;
+    if options.format == Format::Plain {
+        let severity = report.severity();
+        println!("Overall: {severity} ({})", severity.reason());
+    }
+    if stats {
+        let counts = &report.stats;
+        let lines = format!(
+            "Env var names take up {} bytes of static data.\n\
+             Analyzed {} function(s): {} env call site(s), {} string(s) read, {} rejected.",
+            report.name_bytes(),
+            counts.functions_analyzed,
+            counts.env_call_sites,
+            counts.strings_read,
+            counts.strings_rejected
+        );
+        // Keep stdout a single JSON document, or nothing but names
+        if matches!(options.format, Format::Json | Format::Sarif | Format::Names) {
+            eprintln!("{lines}");
+        } else {
+            println!("{lines}");
         }
+    }
+    Ok(())
+}
 
-        println!("\nConfigure these in wasmcloud before deployment.");
+fn run_check_env(args: &CheckEnvArgs) -> Result<ExitCode> {
+    let (_, wasm_bytes) = read_input(&args.input)?;
+    let report = scan(&wasm_bytes, &args.scan.config())?;
+    check_env_file(&args.env_file, &report.names())
+}
+
+/// Pre-deploy readiness gate: fail if any detected variable has no (non-empty)
+/// value in the env file.
+fn check_env_file(env_path: &str, env_vars: &[String]) -> Result<ExitCode> {
+    let provided = dotenv::load(env_path)?;
+    let source = if env_path == "-" {
+        "the current environment"
+    } else {
+        env_path
+    };
+
+    let missing: Vec<&String> = env_vars
+        .iter()
+        .filter(|var| provided.get(*var).is_none_or(String::is_empty))
+        .collect();
+
+    if missing.is_empty() {
+        println!(
+            "All {} detected variables are set in {source}.",
+            env_vars.len()
+        );
+        return Ok(ExitCode::SUCCESS);
     }
 
-    println!("\n---------------------------------------------------");
+    println!("Missing from {source} ({}):\n", missing.len());
+    for var_name in missing {
+        println!("  {var_name}");
+    }
+    Ok(ExitCode::FAILURE)
+}
+
+/// Fill in options not given on the command line from `--config`, or from
+/// `./wasm2env.toml` if there is one.
+#[cfg(feature = "config")]
+fn apply_config_file(cli: &mut Cli, matches: &ArgMatches) -> Result<()> {
+    let path = match cli.config.as_deref() {
+        Some(path) => path,
+        None if Path::new(DEFAULT_CONFIG_FILE).is_file() => DEFAULT_CONFIG_FILE,
+        None => return Ok(()),
+    };
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {path}"))?;
+    let file = toml::from_str(&contents).with_context(|| format!("Invalid config file {path}"))?;
+    cli.fill_from(file, matches);
     Ok(())
 }
+
+#[cfg(not(feature = "config"))]
+fn apply_config_file(cli: &mut Cli, _matches: &ArgMatches) -> Result<()> {
+    if cli.config.is_some() {
+        bail!("--config requires wasm2env to be built with the `config` feature");
+    }
+    if Path::new(DEFAULT_CONFIG_FILE).is_file() {
+        eprintln!(
+            "Warning: ignoring ./{DEFAULT_CONFIG_FILE}; reading it requires wasm2env to be built with the `config` feature.\n"
+        );
+    }
+    Ok(())
+}
+
+/// Download a module; the final URL (after redirects) is shown in the report.
+#[cfg(feature = "net")]
+fn fetch_url(url: &str) -> Result<(String, Vec<u8>)> {
+    fetch::fetch(url)
+}
+
+#[cfg(not(feature = "net"))]
+fn fetch_url(_url: &str) -> Result<(String, Vec<u8>)> {
+    bail!("--url requires wasm2env to be built with the `net` feature")
+}
+
+#[cfg(feature = "git")]
+fn read_at_git_rev(path: &str, rev: &str) -> Result<Vec<u8>> {
+    git::read_at_rev(path, rev)
+}
+
+#[cfg(not(feature = "git"))]
+fn read_at_git_rev(_path: &str, _rev: &str) -> Result<Vec<u8>> {
+    bail!("--since-git requires wasm2env to be built with the `git` feature")
+}
+
+#[cfg(feature = "gzip")]
+fn gunzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    gzip::decompress(bytes)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gunzip(_bytes: &[u8]) -> Result<Vec<u8>> {
+    bail!("Reading gzip-compressed input requires wasm2env to be built with the `gzip` feature")
+}
+
+#[cfg(feature = "tui")]
+fn browse(path: &str, report: &ScanReport) -> Result<()> {
+    Ok(tui::run(path, report)?)
+}
+
+#[cfg(not(feature = "tui"))]
+fn browse(_path: &str, _report: &ScanReport) -> Result<()> {
+    bail!("--interactive requires wasm2env to be built with the `tui` feature")
+}
+
+#[cfg(feature = "watch")]
+fn watch_file(path: &str, mut rescan: impl FnMut(&str)) -> Result<()> {
+    watch::watch(path, || rescan(path))
+}
+
+#[cfg(not(feature = "watch"))]
+fn watch_file(_path: &str, _rescan: impl FnMut(&str)) -> Result<()> {
+    bail!("--watch requires wasm2env to be built with the `watch` feature")
+}
+
+#[cfg(feature = "dir")]
+fn wasm_files(dir: &str) -> Result<Vec<Result<PathBuf>>> {
+    dir::wasm_files(dir)
+}
+
+#[cfg(not(feature = "dir"))]
+fn wasm_files(_dir: &str) -> Result<Vec<Result<PathBuf>>> {
+    bail!("Scanning a directory requires wasm2env to be built with the `dir` feature")
+}
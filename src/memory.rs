@@ -0,0 +1,77 @@
+//! The initial contents of each core module's linear memory, for callers
+//! running their own passes over it.
+
+use std::collections::BTreeMap;
+
+use crate::analysis::load_module;
+use crate::const_fold::fold_extended_consts;
+use crate::extract::CoreModules;
+use crate::report::ScanStats;
+use crate::strings::{active_data_segments, build_memory_map, collect_globals, MemoryMap};
+
+/// What a core module puts in linear memory before any code runs: its active
+/// data segments and constant globals.
+///
+/// Passive segments are left out, since where they land depends on the code
+/// that copies them in.
+#[derive(Debug)]
+pub struct ModuleMemory {
+    /// The core module's index in the binary, as in
+    /// [`crate::EnvVarHit::module_index`].
+    pub module_index: usize,
+    /// `(address, bytes)` of each active data segment whose address is a
+    /// constant, in module order. Later segments overwrite earlier ones where
+    /// they overlap.
+    pub data_segments: Vec<(u32, Vec<u8>)>,
+    /// The value of each constant `i32` global (and `i64` global of a
+    /// memory64 module that fits in 32 bits), by global index. A PIC
+    /// module's imported `__memory_base` is included as 0.
+    pub globals: BTreeMap<u32, i32>,
+    map: MemoryMap,
+}
+
+impl ModuleMemory {
+    fn new(module_index: usize, module: &walrus::Module) -> Self {
+        Self {
+            module_index,
+            data_segments: active_data_segments(module)
+                .into_iter()
+                .map(|(_, addr, bytes)| (addr, bytes.to_vec()))
+                .collect(),
+            globals: collect_globals(module)
+                .into_iter()
+                .map(|(global, value)| (global.index() as u32, value))
+                .collect(),
+            map: build_memory_map(module),
+        }
+    }
+
+    /// The `len` bytes at `ptr`, if they're all initialized. The bytes may
+    /// span several adjacent segments.
+    pub fn read_bytes(&self, ptr: u32, len: u32) -> Option<&[u8]> {
+        self.map.slice(ptr, len)
+    }
+
+    /// The `len` bytes at `ptr` as a string, if they're all initialized and
+    /// valid UTF-8.
+    pub fn read_string(&self, ptr: u32, len: u32) -> Option<&str> {
+        std::str::from_utf8(self.read_bytes(ptr, len)?).ok()
+    }
+}
+
+/// The memory of every core module walrus can load, in module order. Modules
+/// it can't load are left out.
+pub fn module_memories(core_modules: &CoreModules) -> Vec<ModuleMemory> {
+    // Skipped functions don't matter here
+    let mut stats = ScanStats::default();
+    core_modules
+        .modules
+        .iter()
+        .enumerate()
+        .filter_map(|(module_index, core_module)| {
+            let module_bytes = fold_extended_consts(&core_module.bytes);
+            let module = load_module(&module_bytes, module_index, &mut stats)?;
+            Some(ModuleMemory::new(module_index, &module))
+        })
+        .collect()
+}
@@ -0,0 +1,704 @@
+//! Output formats for the CLI.
+
+use std::cmp::Reverse;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use anyhow::{bail, Error};
+use wasm2env::{write_json_string, Category, Confidence, EnvVarHit, HitSource, ScanReport};
+
+const RULE: &str = "---------------------------------------------------";
+
+/// What a module that [`ScanReport::reads_whole_environment`] does.
+const WHOLE_ENVIRONMENT: &str = "reads the whole environment (environ_get / get-environment)";
+
+/// How the CLI prints detected variables.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(rename_all = "kebab-case"))]
+pub enum Format {
+    /// Human-readable numbered list.
+    #[default]
+    Plain,
+    /// `ENV` lines to paste into a Dockerfile.
+    Dockerfile,
+    /// One line per category, with counts.
+    CategorySummary,
+    /// A JSON array of `{"name": ..., "category": ...}` objects, for scripts.
+    Json,
+    /// A SARIF 2.1.0 log with one result per variable, for code scanning.
+    Sarif,
+    /// Just the names, one per line, for shell loops.
+    Names,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Format::Plain),
+            "dockerfile" => Ok(Format::Dockerfile),
+            "category-summary" => Ok(Format::CategorySummary),
+            "json" => Ok(Format::Json),
+            "sarif" => Ok(Format::Sarif),
+            "names" => Ok(Format::Names),
+            other => {
+                bail!(
+                    "Unknown format '{other}' (expected: plain, dockerfile, category-summary, \
+                     json, sarif, names)"
+                )
+            }
+        }
+    }
+}
+
+/// Output settings shared by every format.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(default, deny_unknown_fields))]
+pub struct RenderOptions {
+    pub format: Format,
+    /// Report at most this many variables, the most confident first; the
+    /// rest are summarized in one line.
+    pub limit: Option<usize>,
+    /// Print names lowercased, merging names that only differ in case.
+    pub lowercase: bool,
+}
+
+/// Render the scan result for `path`. When no names were found but the
+/// module reads the whole environment, every format but `names` says so
+/// instead of reporting no dependencies.
+pub fn render(
+    out: &mut impl Write,
+    options: &RenderOptions,
+    path: &str,
+    report: &ScanReport,
+) -> io::Result<()> {
+    let hits = &report.hits;
+    let whole_environment = hits.is_empty() && report.reads_whole_environment();
+    let env_vars = if options.lowercase {
+        lowercase_names(&report.names())
+    } else {
+        report.names()
+    };
+
+    let env_vars = if options.limit.is_some() {
+        by_confidence(&env_vars, hits, options.lowercase)
+    } else {
+        env_vars
+    };
+
+    let shown = options
+        .limit
+        .map_or(env_vars.len(), |limit| limit.min(env_vars.len()));
+    let (env_vars, suppressed) = (&env_vars[..shown], env_vars.len() - shown);
+
+    match options.format {
+        Format::Plain => render_plain(out, path, env_vars, suppressed, whole_environment),
+        Format::Dockerfile => render_dockerfile(out, path, env_vars, suppressed, whole_environment),
+        Format::CategorySummary => {
+            render_category_summary(out, path, env_vars, suppressed, whole_environment)
+        }
+        Format::Json => render_json(out, env_vars, hits, options.lowercase, whole_environment),
+        Format::Sarif => render_sarif(
+            out,
+            path,
+            env_vars,
+            hits,
+            options.lowercase,
+            whole_environment,
+        ),
+        Format::Names => render_names(out, env_vars),
+    }
+}
+
+/// The hits behind `name`, which was lowercased for display if `lowercase`.
+fn hits_named<'a>(
+    hits: &'a [EnvVarHit],
+    name: &'a str,
+    lowercase: bool,
+) -> impl Iterator<Item = &'a EnvVarHit> {
+    hits.iter().filter(move |hit| {
+        if lowercase {
+            hit.name.to_lowercase() == name
+        } else {
+            hit.name == name
+        }
+    })
+}
+
+/// Order names by their most confident hit, highest first, then by name, so
+/// `--limit` cuts the findings least likely to be real.
+fn by_confidence(env_vars: &[String], hits: &[EnvVarHit], lowercase: bool) -> Vec<String> {
+    let confidence = |name: &str| {
+        hits_named(hits, name, lowercase)
+            .map(|hit| hit.confidence)
+            .max()
+    };
+    let mut names = env_vars.to_vec();
+    names.sort_by_cached_key(|name| (Reverse(confidence(name)), name.clone()));
+    names
+}
+
+/// Lowercase every name, keeping the list sorted and de-duplicated.
+fn lowercase_names(env_vars: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = env_vars.iter().map(|name| name.to_lowercase()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn render_plain(
+    out: &mut impl Write,
+    path: &str,
+    env_vars: &[String],
+    suppressed: usize,
+    whole_environment: bool,
+) -> io::Result<()> {
+    writeln!(out, "Analyzing WASM module for environment dependencies...")?;
+    writeln!(out, "File: {path}")?;
+    writeln!(out, "{RULE}\n")?;
+
+    if whole_environment {
+        writeln!(
+            out,
+            "No environment variable names detected, but the module {WHOLE_ENVIRONMENT}\n\
+             and may look up any variable at runtime."
+        )?;
+    } else if env_vars.is_empty() && suppressed == 0 {
+        writeln!(out, "No environment variable dependencies detected.")?;
+    } else {
+        writeln!(
+            out,
+            "Required Environment Variables ({}):\n",
+            env_vars.len() + suppressed
+        )?;
+
+        for (i, var_name) in env_vars.iter().enumerate() {
+            writeln!(out, "  {}. {}", i + 1, var_name)?;
+        }
+        if suppressed > 0 {
+            writeln!(out, "  (… and {suppressed} more suppressed)")?;
+        }
+
+        writeln!(out, "\nConfigure these in wasmcloud before deployment.")?;
+    }
+
+    writeln!(out, "\n{RULE}")
+}
+
+/// Each name on its own line and nothing else, not even a note about names
+/// left out by `--limit`.
+fn render_names(out: &mut impl Write, env_vars: &[String]) -> io::Result<()> {
+    for var_name in env_vars {
+        writeln!(out, "{var_name}")?;
+    }
+    Ok(())
+}
+
+/// One `ENV NAME=` line per variable. Secrets get a comment instead, since
+/// anything set with `ENV` is baked into the image layers.
+fn render_dockerfile(
+    out: &mut impl Write,
+    path: &str,
+    env_vars: &[String],
+    suppressed: usize,
+    whole_environment: bool,
+) -> io::Result<()> {
+    writeln!(out, "# Environment variables required by {path}")?;
+
+    for var_name in env_vars {
+        if Category::of(var_name) == Category::Secret {
+            writeln!(
+                out,
+                "# {var_name} is a secret: pass it at runtime (e.g. `docker run -e {var_name}`), \
+                 don't bake it into an image layer"
+            )?;
+        } else {
+            writeln!(out, "ENV {var_name}=")?;
+        }
+    }
+    if suppressed > 0 {
+        writeln!(out, "# (… and {suppressed} more suppressed)")?;
+    }
+    if whole_environment {
+        writeln!(
+            out,
+            "# No variable names found, but the module {WHOLE_ENVIRONMENT}"
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Group the variables by [`Category`]: `Secrets (2): API_KEY, JWT_SECRET`.
+/// Empty categories are left out.
+fn render_category_summary(
+    out: &mut impl Write,
+    path: &str,
+    env_vars: &[String],
+    suppressed: usize,
+    whole_environment: bool,
+) -> io::Result<()> {
+    writeln!(out, "Environment variables in {path} by category:")?;
+    writeln!(out, "{RULE}\n")?;
+
+    for (category, heading) in [
+        (Category::Secret, "Secrets"),
+        (Category::Url, "URLs"),
+        (Category::Generic, "Generic"),
+    ] {
+        let names: Vec<&str> = env_vars
+            .iter()
+            .filter(|name| Category::of(name) == category)
+            .map(String::as_str)
+            .collect();
+        if !names.is_empty() {
+            writeln!(out, "  {heading} ({}): {}", names.len(), names.join(", "))?;
+        }
+    }
+    if suppressed > 0 {
+        writeln!(out, "  (… and {suppressed} more suppressed)")?;
+    }
+
+    writeln!(out, "\nTotal: {}", env_vars.len() + suppressed)?;
+    if whole_environment {
+        writeln!(
+            out,
+            "No variable names found, but the module {WHOLE_ENVIRONMENT}."
+        )?;
+    }
+    writeln!(out, "\n{RULE}")
+}
+
+/// One object per variable on one line, e.g.
+/// `{"name":"API_KEY","category":"secret","confidence":"high","sources":["call_site"],"functions":["app::config::load"]}`:
+/// the confidence of its most confident hit, how it was found and, where the
+/// name section has them, the functions reading it (left out when there are
+/// none). Suppressed names are simply left out, so the output stays a plain
+/// array. Instead of `[]`, a module that reads the whole environment gets a
+/// single `{"name":null,"whole_environment":true}` element, so the result
+/// can't pass for "reads nothing".
+fn render_json(
+    out: &mut impl Write,
+    env_vars: &[String],
+    hits: &[EnvVarHit],
+    lowercase: bool,
+    whole_environment: bool,
+) -> io::Result<()> {
+    write!(out, "[")?;
+    for (i, var_name) in env_vars.iter().enumerate() {
+        let category = match Category::of(var_name) {
+            Category::Secret => "secret",
+            Category::Url => "url",
+            Category::Generic => "generic",
+        };
+        let found: Vec<&EnvVarHit> = hits_named(hits, var_name, lowercase).collect();
+        let mut sources: Vec<HitSource> = found.iter().map(|hit| hit.source).collect();
+        sources.sort_unstable();
+        sources.dedup();
+        let mut functions: Vec<&str> = found
+            .iter()
+            .filter_map(|hit| hit.function_name.as_deref())
+            .collect();
+        functions.sort_unstable();
+        functions.dedup();
+
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        write!(out, r#"{{"name":"#)?;
+        write_json_string(out, var_name)?;
+        write!(out, r#","category":"{category}""#)?;
+        if let Some(confidence) = found.iter().map(|hit| hit.confidence).max() {
+            write!(out, r#","confidence":"{}""#, confidence_name(confidence))?;
+        }
+        if !sources.is_empty() {
+            let sources: Vec<String> = sources
+                .iter()
+                .map(|source| format!(r#""{}""#, source_name(*source)))
+                .collect();
+            write!(out, r#","sources":[{}]"#, sources.join(","))?;
+        }
+        if !functions.is_empty() {
+            write!(out, r#","functions":["#)?;
+            for (j, function) in functions.iter().enumerate() {
+                if j > 0 {
+                    write!(out, ",")?;
+                }
+                write_json_string(out, function)?;
+            }
+            write!(out, "]")?;
+        }
+        write!(out, "}}")?;
+    }
+    if whole_environment {
+        write!(out, r#"{{"name":null,"whole_environment":true}}"#)?;
+    }
+    writeln!(out, "]")
+}
+
+/// `confidence` as the library's serde feature spells it.
+fn confidence_name(confidence: Confidence) -> &'static str {
+    match confidence {
+        Confidence::Low => "low",
+        Confidence::Medium => "medium",
+        Confidence::High => "high",
+    }
+}
+
+/// `source` as the library's serde feature spells it.
+fn source_name(source: HitSource) -> &'static str {
+    match source {
+        HitSource::CallSite => "call_site",
+        HitSource::ExportName => "export_name",
+        HitSource::DataString => "data_string",
+        HitSource::LogMessage => "log_message",
+        HitSource::DebugInfo => "debug_info",
+    }
+}
+
+/// A minimal SARIF 2.1.0 log: one `env-var` result per variable, located in
+/// `path` and, where the name section has them, in the functions reading it.
+/// Secrets are warnings, everything else a note. Like JSON, suppressed names
+/// are left out. With no names, reading the whole environment is a
+/// `whole-environment` note instead.
+fn render_sarif(
+    out: &mut impl Write,
+    path: &str,
+    env_vars: &[String],
+    hits: &[EnvVarHit],
+    lowercase: bool,
+    whole_environment: bool,
+) -> io::Result<()> {
+    write!(
+        out,
+        r#"{{"$schema":"https://json.schemastore.org/sarif-2.1.0.json","version":"2.1.0","runs":[{{"tool":{{"driver":{{"name":"wasm2env","version":"{}","informationUri":"https://github.com/Aditya1404Sal/wasm2env","rules":[{{"id":"env-var","shortDescription":{{"text":"Environment variable dependency"}}}},{{"id":"whole-environment","shortDescription":{{"text":"Reads the whole environment"}}}}]}}}},"results":["#,
+        env!("CARGO_PKG_VERSION")
+    )?;
+    let uri = json_string(path);
+    for (i, var_name) in env_vars.iter().enumerate() {
+        let level = if Category::of(var_name) == Category::Secret {
+            "warning"
+        } else {
+            "note"
+        };
+        let message = json_string(&format!("Reads environment variable {var_name}"));
+        let mut functions: Vec<&str> = hits_named(hits, var_name, lowercase)
+            .filter_map(|hit| hit.function_name.as_deref())
+            .collect();
+        functions.sort_unstable();
+        functions.dedup();
+        let logical: Vec<String> = functions
+            .iter()
+            .map(|name| format!(r#"{{"name":{},"kind":"function"}}"#, json_string(name)))
+            .collect();
+
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        write!(
+            out,
+            r#"{{"ruleId":"env-var","level":"{level}","message":{{"text":{message}}},"locations":[{{"physicalLocation":{{"artifactLocation":{{"uri":{uri}}}}}"#
+        )?;
+        if !logical.is_empty() {
+            write!(out, r#","logicalLocations":[{}]"#, logical.join(","))?;
+        }
+        write!(out, "}}]}}")?;
+    }
+    if whole_environment {
+        write!(
+            out,
+            r#"{{"ruleId":"whole-environment","level":"note","message":{{"text":"No variable names found, but the module {WHOLE_ENVIRONMENT}"}},"locations":[{{"physicalLocation":{{"artifactLocation":{{"uri":{uri}}}}}}}]}}"#
+        )?;
+    }
+    writeln!(out, "]}}]}}")
+}
+
+/// `s` as a quoted JSON string, for splicing into a format string.
+fn json_string(s: &str) -> String {
+    let mut quoted = Vec::new();
+    write_json_string(&mut quoted, s).expect("writing to a Vec can't fail");
+    String::from_utf8(quoted).expect("escaping keeps the string UTF-8")
+}
+
+/// List the variables added and removed since `baseline` (e.g. a git
+/// revision). Both lists must be sorted.
+pub fn render_diff(
+    out: &mut impl Write,
+    path: &str,
+    baseline: &str,
+    before: &[String],
+    after: &[String],
+) -> io::Result<()> {
+    let added: Vec<&String> = after
+        .iter()
+        .filter(|name| before.binary_search(name).is_err())
+        .collect();
+    let removed: Vec<&String> = before
+        .iter()
+        .filter(|name| after.binary_search(name).is_err())
+        .collect();
+
+    writeln!(
+        out,
+        "Environment variable changes in {path} since {baseline}:"
+    )?;
+    writeln!(out, "{RULE}\n")?;
+    if added.is_empty() && removed.is_empty() {
+        writeln!(out, "No changes.")?;
+    }
+    for var_name in added {
+        writeln!(out, "  + {var_name}")?;
+    }
+    for var_name in removed {
+        writeln!(out, "  - {var_name}")?;
+    }
+    writeln!(out, "\n{RULE}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm2env::NameRule;
+
+    fn render_to_string(options: &RenderOptions, env_vars: &[String]) -> String {
+        let report = ScanReport {
+            hits: env_vars
+                .iter()
+                .map(|name| hit(name, Confidence::High))
+                .collect(),
+            ..ScanReport::default()
+        };
+        render_report(options, &report)
+    }
+
+    fn render_report(options: &RenderOptions, report: &ScanReport) -> String {
+        let mut out = Vec::new();
+        render(&mut out, options, "app.wasm", report).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn hit(name: &str, confidence: Confidence) -> EnvVarHit {
+        EnvVarHit {
+            name: name.to_string(),
+            ptr: Some(1024),
+            file_offset: None,
+            len: name.len() as u32,
+            module_index: 0,
+            function_index: Some(3),
+            function_name: None,
+            source: HitSource::CallSite,
+            rule: NameRule::UpperSnake,
+            confidence,
+            score: 85,
+            sink: None,
+            default: None,
+            suspicious_overlap: false,
+            corroborated: false,
+        }
+    }
+
+    #[test]
+    fn test_render_dockerfile() {
+        let vars = vec!["API_KEY".to_string(), "DATABASE_URL".to_string()];
+        let options = RenderOptions {
+            format: Format::Dockerfile,
+            ..RenderOptions::default()
+        };
+        let out = render_to_string(&options, &vars);
+
+        assert!(out.starts_with("# Environment variables required by app.wasm\n"));
+        assert!(out.contains("ENV DATABASE_URL=\n"));
+        assert!(!out.contains("ENV API_KEY"));
+        assert!(out.contains("# API_KEY is a secret"));
+    }
+
+    #[test]
+    fn test_render_limit() {
+        let hits = vec![
+            hit("API_KEY", Confidence::Low),
+            hit("DATABASE_URL", Confidence::Low),
+            hit("DATABASE_URL", Confidence::High),
+            hit("JWT_SECRET", Confidence::Medium),
+            hit("LOG_LEVEL", Confidence::High),
+        ];
+        let options = RenderOptions {
+            limit: Some(3),
+            ..RenderOptions::default()
+        };
+        let report = ScanReport {
+            hits,
+            ..ScanReport::default()
+        };
+        let out = render_report(&options, &report);
+
+        assert!(out.contains("Required Environment Variables (4):"));
+        // Most confident first, then by name; API_KEY sorts first but is cut
+        assert!(out.contains("  1. DATABASE_URL\n  2. LOG_LEVEL\n  3. JWT_SECRET\n"));
+        assert!(!out.contains("API_KEY"));
+        assert!(out.contains("(… and 1 more suppressed)"));
+    }
+
+    #[test]
+    fn test_render_lowercase() {
+        let vars: Vec<String> = ["API_KEY", "Api_Key", "DATABASE_URL"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let options = RenderOptions {
+            lowercase: true,
+            ..RenderOptions::default()
+        };
+        let out = render_to_string(&options, &vars);
+
+        assert!(out.contains("Required Environment Variables (2):"));
+        assert!(out.contains("  1. api_key\n"));
+        assert!(out.contains("  2. database_url\n"));
+    }
+
+    #[test]
+    fn test_render_category_summary() {
+        let vars: Vec<String> = ["API_KEY", "DATABASE_URL", "JWT_SECRET", "LOG_LEVEL"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let options = RenderOptions {
+            format: Format::CategorySummary,
+            ..RenderOptions::default()
+        };
+        let out = render_to_string(&options, &vars);
+
+        assert!(out.contains("  Secrets (2): API_KEY, JWT_SECRET\n"));
+        assert!(out.contains("  URLs (1): DATABASE_URL\n"));
+        assert!(out.contains("  Generic (1): LOG_LEVEL\n"));
+        assert!(out.contains("Total: 4\n"));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let report = ScanReport {
+            hits: vec![
+                EnvVarHit {
+                    function_name: Some("app::config::load".to_string()),
+                    ..hit("API_KEY", Confidence::Medium)
+                },
+                EnvVarHit {
+                    source: HitSource::LogMessage,
+                    ..hit("API_KEY", Confidence::Low)
+                },
+                hit("app.\"quoted\"", Confidence::High),
+            ],
+            ..ScanReport::default()
+        };
+        let options = RenderOptions {
+            format: Format::Json,
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(
+            render_report(&options, &report),
+            r#"[{"name":"API_KEY","category":"secret","confidence":"medium","sources":["call_site","log_message"],"functions":["app::config::load"]},{"name":"app.\"quoted\"","category":"generic","confidence":"high","sources":["call_site"]}]"#
+                .to_string()
+                + "\n"
+        );
+    }
+
+    #[test]
+    fn test_render_names() {
+        let vars = vec![
+            "API_KEY".to_string(),
+            "DATABASE_URL".to_string(),
+            "LOG_LEVEL".to_string(),
+        ];
+        let options = RenderOptions {
+            format: Format::Names,
+            limit: Some(2),
+            ..RenderOptions::default()
+        };
+        assert_eq!(render_to_string(&options, &vars), "API_KEY\nDATABASE_URL\n");
+        assert_eq!(render_to_string(&options, &[]), "");
+    }
+
+    #[test]
+    fn test_render_whole_environment() {
+        let mut report = ScanReport::default();
+        report.stats.whole_environment_modules.push(0);
+        let format = |format| RenderOptions {
+            format,
+            ..RenderOptions::default()
+        };
+
+        let out = render_report(&format(Format::Plain), &report);
+        assert!(out.contains("No environment variable names detected, but the module reads"));
+        assert!(!out.contains("No environment variable dependencies detected."));
+        assert_eq!(
+            render_report(&format(Format::Json), &report),
+            "[{\"name\":null,\"whole_environment\":true}]\n"
+        );
+        assert_eq!(render_report(&format(Format::Names), &report), "");
+
+        assert!(render_report(&format(Format::Sarif), &report)
+            .contains(r#"{"ruleId":"whole-environment","level":"note""#));
+
+        // Found names are reported as usual
+        report.hits.push(hit("LOG_LEVEL", Confidence::High));
+        assert_eq!(
+            render_report(&format(Format::Json), &report),
+            "[{\"name\":\"LOG_LEVEL\",\"category\":\"generic\",\"confidence\":\"high\",\"sources\":[\"call_site\"]}]\n"
+        );
+    }
+
+    #[test]
+    fn test_render_sarif() {
+        let report = ScanReport {
+            hits: vec![
+                EnvVarHit {
+                    function_name: Some("app::config::load".to_string()),
+                    ..hit("API_KEY", Confidence::High)
+                },
+                hit("LOG_LEVEL", Confidence::High),
+                hit("app.\"quoted\"", Confidence::High),
+            ],
+            ..ScanReport::default()
+        };
+        let options = RenderOptions {
+            format: Format::Sarif,
+            ..RenderOptions::default()
+        };
+        let mut out = Vec::new();
+        render(&mut out, &options, "dir/app.wasm", &report).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.starts_with(r#"{"$schema":"https://json.schemastore.org/sarif-2.1.0.json","version":"2.1.0","runs":[{"tool":{"driver":{"name":"wasm2env""#));
+        assert!(out.contains(
+            r#"{"ruleId":"env-var","level":"warning","message":{"text":"Reads environment variable API_KEY"},"locations":[{"physicalLocation":{"artifactLocation":{"uri":"dir/app.wasm"}},"logicalLocations":[{"name":"app::config::load","kind":"function"}]}]}"#
+        ));
+        assert!(out.contains(
+            r#"{"ruleId":"env-var","level":"note","message":{"text":"Reads environment variable LOG_LEVEL"},"locations":[{"physicalLocation":{"artifactLocation":{"uri":"dir/app.wasm"}}}]}"#
+        ));
+        assert!(out.contains(r#""message":{"text":"Reads environment variable app.\"quoted\""}"#));
+        assert!(out.ends_with("]}]}\n"));
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let before: Vec<String> = ["API_KEY", "LOG_LEVEL"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let after: Vec<String> = ["DATABASE_URL", "LOG_LEVEL"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let mut out = Vec::new();
+        render_diff(&mut out, "app.wasm", "main", &before, &after).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.starts_with("Environment variable changes in app.wasm since main:\n"));
+        assert!(out.contains("  + DATABASE_URL\n"));
+        assert!(out.contains("  - API_KEY\n"));
+        assert!(!out.contains("LOG_LEVEL"));
+    }
+}
@@ -0,0 +1,562 @@
+//! Detailed scan results.
+//!
+//! While [`crate::scan_wasm_bytes`] only returns variable names, the detailed
+//! pass keeps one [`EnvVarHit`] per call site so tooling can point users at
+//! the exact function and data address behind each detection.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::category::Category;
+
+/// How much a hit can be trusted, derived from [`EnvVarHit::score`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Confidence {
+    /// Name-shaped string with no data flow into an env API (e.g. an export name).
+    Low,
+    /// Traced into an env API, but the name itself looks unusual.
+    Medium,
+    /// String argument traced into a WASI env/config import.
+    High,
+}
+
+/// Where a hit was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum HitSource {
+    /// A string argument at a call site reaching an env/config import.
+    CallSite,
+    /// The name of a module export (opt-in via [`crate::ScanConfig::scan_exports`]).
+    ExportName,
+    /// A string in the data segments of a relocatable object file, where
+    /// call sites can't be traced (see [`ScanStats::relocatable_modules`]).
+    DataString,
+    /// A word in a message written with WASI `fd_write` (opt-in via
+    /// [`crate::ScanConfig::scan_logs`]).
+    LogMessage,
+    /// A string in the DWARF `.debug_str` section (opt-in via
+    /// [`crate::ScanConfig::debug_info`]). May be stale: debug info can name
+    /// code that was optimized out.
+    DebugInfo,
+}
+
+/// Which naming rule of [`crate::is_valid_env_name`] accepted a hit's name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum NameRule {
+    /// Separated parts with no lowercase letters (`DATABASE_URL`, `FEATURE-FLAG-X`).
+    UpperSnake,
+    /// A single all-caps word of at least [`crate::ScanConfig::min_word_length`]
+    /// characters (`PORT`).
+    AllCapsWord,
+    /// Any other casing whose last part is a config keyword (`database_url`,
+    /// `app.api.key`).
+    Keyword,
+    /// Any other separated name (`my_var`). Only trusted at call sites traced
+    /// into an env import.
+    Separated,
+}
+
+/// A single environment variable reference found in a module.
+///
+/// Hits order by name first, then by where they were found.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnvVarHit {
+    /// The detected variable name.
+    pub name: String,
+    /// Linear-memory address the name was read from (call-site hits only).
+    pub ptr: Option<u32>,
+    /// Offset of the name's first byte in the scanned file, i.e. within the
+    /// data section that initializes it, for cross-referencing with a hex
+    /// dump. `None` when the bytes don't come from a data segment (or the
+    /// hit isn't backed by memory at all, like export names), and for
+    /// [`crate::scan_walrus_module`], which has no file to point into.
+    pub file_offset: Option<usize>,
+    /// Length in bytes the name was read with, i.e. the size of the name in
+    /// the data section (excluding any NUL terminator).
+    pub len: u32,
+    /// Index of the core module (within a component) the hit was found in.
+    pub module_index: usize,
+    /// Index of the function whose body contains the call site, or of the
+    /// exported function for export-name hits.
+    pub function_index: Option<u32>,
+    /// That function's symbol from the module's `name` section (e.g.
+    /// `myapp::config::load`), when the module has one. Debug builds and
+    /// many release builds keep it.
+    pub function_name: Option<String>,
+    pub source: HitSource,
+    /// The naming rule the name passed, e.g. to tell `PORT` (a lone all-caps
+    /// word) from `DATABASE_URL` when auditing false positives.
+    pub rule: NameRule,
+    pub confidence: Confidence,
+    /// Raw 0–100 score `confidence` was derived from: whether the name was
+    /// traced into an env import, plus hints from the name's shape.
+    pub score: u8,
+    /// The env/config import the name is ultimately passed to, as
+    /// `module.name` (e.g. `wasi:config/store@0.2.0-draft.get`). `None` for
+    /// hits that aren't call sites.
+    pub sink: Option<String>,
+    /// A fallback value found next to the call site, e.g. `8080` for
+    /// `env::var("PORT").unwrap_or("8080".into())` or
+    /// `getenv("PORT") ?: "8080"`. Best-effort: only string literals
+    /// handled within a few calls of the read are recognized.
+    pub default: Option<String>,
+    /// Set when another hit reads the same pointer with a different length,
+    /// which usually means one of the two lengths was mis-inferred.
+    pub suspicious_overlap: bool,
+    /// Set when the same name was also found by another detection method
+    /// (a different [`HitSource`]), e.g. both at a call site and in a logged
+    /// error message. Corroborated hits are [`Confidence::High`] whatever
+    /// their score.
+    pub corroborated: bool,
+}
+
+/// At-a-glance verdict for a whole module; see [`ScanReport::severity`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// No environment variables are read.
+    None,
+    /// No names were found, but the whole environment is read (see
+    /// [`ScanReport::reads_whole_environment`]), so which variables matter
+    /// can't be told.
+    Unknown,
+    /// A single non-secret variable is read.
+    Low,
+    /// Several non-secret variables are read.
+    Medium,
+    /// At least one variable is a secret ([`Category::Secret`]).
+    High,
+}
+
+impl Severity {
+    /// One-line explanation of the verdict.
+    pub fn reason(self) -> &'static str {
+        match self {
+            Severity::None => "no environment access detected",
+            Severity::Unknown => "reads the whole environment, names unknown",
+            Severity::Low => "reads a single config variable",
+            Severity::Medium => "reads several config variables",
+            Severity::High => "reads secrets directly",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::None => "NONE",
+            Severity::Unknown => "UNKNOWN",
+            Severity::Low => "LOW",
+            Severity::Medium => "MEDIUM",
+            Severity::High => "HIGH",
+        })
+    }
+}
+
+/// Result of a detailed scan.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScanReport {
+    /// One hit per env-related call-site read (plus any opt-in passes), in
+    /// the order documented on [`crate::scan_wasm_bytes_detailed`].
+    pub hits: Vec<EnvVarHit>,
+    /// Byte offset at which the input ended unexpectedly. When set, `hits`
+    /// only covers the core modules that were complete before that point.
+    pub truncated_at: Option<usize>,
+    pub stats: ScanStats,
+}
+
+/// What the scanner saw, and what it had to leave out.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScanStats {
+    /// Functions whose bodies couldn't be loaded (typically because they use
+    /// a wasm proposal walrus doesn't support yet) and were not analyzed.
+    pub skipped_functions: Vec<SkippedFunction>,
+    /// `(module index, function index)` of functions that call into an env
+    /// import with arguments no name could be recovered from — they read
+    /// *some* variable, but we can't say which.
+    pub unresolved_env_reads: Vec<(usize, u32)>,
+    /// Core modules that are relocatable object files (`.o` files not yet
+    /// linked by `wasm-ld`). Their addresses aren't final, so instead of
+    /// tracing call sites, every env-like string in their data is reported
+    /// with low confidence.
+    pub relocatable_modules: Vec<usize>,
+    /// `(candidate, reason)` for every distinct string that was read from an
+    /// env call's arguments but rejected as a name, e.g.
+    /// `("RUST_BACKTRACE", "in the noise list")`.
+    pub rejected_candidates: Vec<(String, String)>,
+    /// Core modules that import an API returning the entire environment
+    /// (WASI `environ_get`, or `get-environment` from `wasi:cli/environment`).
+    /// Such a module may enumerate variables at runtime, so names that never
+    /// appear as literals can't be detected; an empty result doesn't mean it
+    /// reads nothing.
+    pub whole_environment_modules: Vec<usize>,
+    /// `wasi:cli/environment` interfaces the component itself imports, e.g.
+    /// `wasi:cli/environment@0.2.0`. Seen even when none of its core modules
+    /// could be analyzed; like [`Self::whole_environment_modules`], it means
+    /// the environment is reachable whether or not any names were found.
+    pub environment_imports: Vec<String>,
+    /// Functions walked in the call-site pass, over all core modules that
+    /// import an env API.
+    pub functions_analyzed: usize,
+    /// Calls with arguments into a function that reaches an env API, i.e.
+    /// every place a name could have been read.
+    pub env_call_sites: usize,
+    /// Strings read from those calls' arguments, valid names or not.
+    pub strings_read: usize,
+    /// How many of [`Self::strings_read`] failed the name filters; the
+    /// distinct ones are in [`Self::rejected_candidates`] with the reason.
+    pub strings_rejected: usize,
+}
+
+/// A function left out of the analysis.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SkippedFunction {
+    pub module_index: usize,
+    pub function_index: u32,
+    /// The validation error that caused it to be skipped.
+    pub reason: String,
+}
+
+impl ScanReport {
+    /// Sorted, de-duplicated variable names across all hits.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.hits.iter().map(|hit| hit.name.clone()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Each variable's default value, where one was found (see
+    /// [`EnvVarHit::default`]). If hits disagree, the first one wins.
+    pub fn defaults(&self) -> HashMap<&str, &str> {
+        let mut defaults = HashMap::new();
+        for hit in &self.hits {
+            if let Some(default) = &hit.default {
+                defaults
+                    .entry(hit.name.as_str())
+                    .or_insert(default.as_str());
+            }
+        }
+        defaults
+    }
+
+    /// Whether a core module or the component itself imports an API that
+    /// returns the entire environment ([`ScanStats::whole_environment_modules`]
+    /// or [`ScanStats::environment_imports`]), so variables may be looked up
+    /// by names that appear nowhere in the module.
+    pub fn reads_whole_environment(&self) -> bool {
+        !(self.stats.whole_environment_modules.is_empty()
+            && self.stats.environment_imports.is_empty())
+    }
+
+    /// Overall severity: `High` if any detected variable is a secret,
+    /// otherwise `Medium`, `Low` or `None` by how many distinct variables are
+    /// read. With no names found, a module that
+    /// [reads the whole environment](Self::reads_whole_environment) is
+    /// `Unknown` rather than `None`.
+    pub fn severity(&self) -> Severity {
+        let names = self.names();
+        if names
+            .iter()
+            .any(|name| Category::of(name) == Category::Secret)
+        {
+            return Severity::High;
+        }
+        match names.len() {
+            0 if self.reads_whole_environment() => Severity::Unknown,
+            0 => Severity::None,
+            1 => Severity::Low,
+            _ => Severity::Medium,
+        }
+    }
+
+    /// Total bytes of static data taken up by detected names. Each string is
+    /// counted once however many call sites read it; hits that weren't read
+    /// from memory (e.g. export names) don't count.
+    pub fn name_bytes(&self) -> u64 {
+        let mut strings: Vec<(usize, u32, u32)> = self
+            .hits
+            .iter()
+            .filter_map(|hit| Some((hit.module_index, hit.ptr?, hit.len)))
+            .collect();
+        strings.sort_unstable();
+        strings.dedup_by_key(|(module_index, ptr, _)| (*module_index, *ptr));
+        strings.iter().map(|(_, _, len)| u64::from(*len)).sum()
+    }
+
+    /// Canonical form for comparisons and snapshot tests: hits sorted by
+    /// name (then location) and de-duplicated, and the stats lists sorted.
+    ///
+    /// Only ordering is normalized; no fields are dropped. Two scans of the
+    /// same bytes normalize to equal reports whichever API produced them,
+    /// except that [`crate::scan_with_callback`] never sets
+    /// `suspicious_overlap` or `corroborated` and doesn't deliver stats.
+    #[must_use]
+    pub fn normalized(mut self) -> Self {
+        self.hits.sort();
+        self.hits.dedup();
+        self.stats.skipped_functions.sort();
+        self.stats.unresolved_env_reads.sort_unstable();
+        self.stats.relocatable_modules.sort_unstable();
+        self.stats.whole_environment_modules.sort_unstable();
+        self.stats.environment_imports.sort();
+        self.stats.rejected_candidates.sort();
+        self.stats.rejected_candidates.dedup();
+        self
+    }
+}
+
+/// Drop every hit for names found in fewer than `min` distinct functions.
+pub(crate) fn retain_min_functions(hits: &mut Vec<EnvVarHit>, min: usize) {
+    if min <= 1 {
+        return;
+    }
+    let mut functions: HashMap<&str, Vec<(usize, Option<u32>)>> = HashMap::new();
+    for hit in hits.iter() {
+        functions
+            .entry(&hit.name)
+            .or_default()
+            .push((hit.module_index, hit.function_index));
+    }
+    let rare: Vec<String> = functions
+        .into_iter()
+        .filter_map(|(name, mut locations)| {
+            locations.sort_unstable();
+            locations.dedup();
+            (locations.len() < min).then(|| name.to_string())
+        })
+        .collect();
+    hits.retain(|hit| !rare.contains(&hit.name));
+}
+
+/// Rename hits whose names differ only in case to one canonical spelling: the
+/// one with the most upper-case letters, then the first in byte order.
+pub(crate) fn canonicalize_case(hits: &mut [EnvVarHit]) {
+    let mut canonical: HashMap<String, (&str, NameRule)> = HashMap::new();
+    for hit in hits.iter() {
+        let (spelling, rule) = canonical
+            .entry(hit.name.to_ascii_uppercase())
+            .or_insert((&hit.name, hit.rule));
+        let uppercase = |name: &str| name.bytes().filter(u8::is_ascii_uppercase).count();
+        if (uppercase(&hit.name), std::cmp::Reverse(hit.name.as_str()))
+            > (uppercase(spelling), std::cmp::Reverse(*spelling))
+        {
+            (*spelling, *rule) = (&hit.name, hit.rule);
+        }
+    }
+    // The rule goes along with the spelling, since it depends on the casing
+    let renames: HashMap<String, (String, NameRule)> = hits
+        .iter()
+        .filter_map(|hit| {
+            let (spelling, rule) = canonical[&hit.name.to_ascii_uppercase()];
+            (spelling != hit.name).then(|| (hit.name.clone(), (spelling.to_string(), rule)))
+        })
+        .collect();
+
+    for hit in hits.iter_mut() {
+        if let Some((spelling, rule)) = renames.get(&hit.name) {
+            hit.name.clone_from(spelling);
+            hit.rule = *rule;
+        }
+    }
+}
+
+/// Flag every hit whose name was found through at least two different
+/// [`HitSource`]s, raising it to [`Confidence::High`].
+pub(crate) fn flag_corroborated(hits: &mut [EnvVarHit]) {
+    let mut sources: HashMap<&str, Vec<HitSource>> = HashMap::new();
+    for hit in hits.iter() {
+        sources.entry(&hit.name).or_default().push(hit.source);
+    }
+    let corroborated: Vec<String> = sources
+        .into_iter()
+        .filter_map(|(name, mut sources)| {
+            sources.sort_unstable();
+            sources.dedup();
+            (sources.len() >= 2).then(|| name.to_string())
+        })
+        .collect();
+
+    for hit in hits.iter_mut() {
+        if corroborated.contains(&hit.name) {
+            hit.corroborated = true;
+            hit.confidence = Confidence::High;
+        }
+    }
+}
+
+/// Flag hits that read the same pointer (within the same core module) with
+/// disagreeing lengths. Every hit in such a group is flagged, since we can't
+/// tell statically which length is the correct one.
+pub(crate) fn flag_suspicious_overlaps(hits: &mut [EnvVarHit]) {
+    let mut lengths: HashMap<(usize, u32), u32> = HashMap::new();
+    let mut conflicting = Vec::new();
+
+    for hit in hits.iter() {
+        let Some(ptr) = hit.ptr else {
+            continue;
+        };
+        let key = (hit.module_index, ptr);
+        match lengths.get(&key) {
+            Some(&len) if len != hit.len => conflicting.push(key),
+            Some(_) => {}
+            None => {
+                lengths.insert(key, hit.len);
+            }
+        }
+    }
+
+    for hit in hits.iter_mut() {
+        if let Some(ptr) = hit.ptr {
+            if conflicting.contains(&(hit.module_index, ptr)) {
+                hit.suspicious_overlap = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(name: &str, ptr: u32, function_index: u32) -> EnvVarHit {
+        EnvVarHit {
+            name: name.to_string(),
+            ptr: Some(ptr),
+            file_offset: None,
+            len: name.len() as u32,
+            module_index: 0,
+            function_index: Some(function_index),
+            function_name: None,
+            source: HitSource::CallSite,
+            rule: NameRule::UpperSnake,
+            confidence: Confidence::High,
+            score: 100,
+            sink: None,
+            default: None,
+            suspicious_overlap: false,
+            corroborated: false,
+        }
+    }
+
+    #[test]
+    fn test_severity() {
+        let report = |names: &[&str]| ScanReport {
+            hits: names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| hit(name, 0x100 * (i as u32 + 1), 1))
+                .collect(),
+            ..ScanReport::default()
+        };
+        assert_eq!(report(&[]).severity(), Severity::None);
+        let mut enumerates = report(&[]);
+        enumerates.stats.whole_environment_modules.push(0);
+        assert_eq!(enumerates.severity(), Severity::Unknown);
+        enumerates.hits.push(hit("LOG_LEVEL", 0x100, 1));
+        assert_eq!(enumerates.severity(), Severity::Low);
+        assert_eq!(report(&["LOG_LEVEL"]).severity(), Severity::Low);
+        assert_eq!(
+            report(&["LOG_LEVEL", "LOG_LEVEL"]).severity(),
+            Severity::Low
+        );
+        assert_eq!(
+            report(&["LOG_LEVEL", "DATABASE_URL"]).severity(),
+            Severity::Medium
+        );
+        assert_eq!(report(&["API_KEY"]).severity(), Severity::High);
+    }
+
+    #[test]
+    fn test_retain_min_functions() {
+        let mut hits = vec![
+            hit("API_KEY", 0x100, 1),
+            hit("API_KEY", 0x100, 1),
+            hit("DATABASE_URL", 0x200, 1),
+            hit("DATABASE_URL", 0x200, 2),
+        ];
+        retain_min_functions(&mut hits, 2);
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|hit| hit.name == "DATABASE_URL"));
+    }
+
+    #[test]
+    fn test_canonicalize_case() {
+        let mut hits = vec![
+            EnvVarHit {
+                rule: NameRule::Keyword,
+                ..hit("Database_Url", 0x100, 1)
+            },
+            hit("DATABASE_URL", 0x200, 2),
+            EnvVarHit {
+                rule: NameRule::Keyword,
+                ..hit("database_url", 0x300, 3)
+            },
+            hit("Api_KEY", 0x400, 1),
+            hit("API_Key", 0x500, 1),
+            hit("LOG_LEVEL", 0x600, 1),
+        ];
+        canonicalize_case(&mut hits);
+        let names: Vec<&str> = hits.iter().map(|hit| hit.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "DATABASE_URL",
+                "DATABASE_URL",
+                "DATABASE_URL",
+                // Tied on upper-case letters: first in byte order
+                "API_Key",
+                "API_Key",
+                "LOG_LEVEL",
+            ]
+        );
+        assert_eq!(hits[0].ptr, Some(0x100));
+        // The rule follows the canonical spelling
+        assert!(hits[..3].iter().all(|hit| hit.rule == NameRule::UpperSnake));
+    }
+
+    #[test]
+    fn test_name_bytes() {
+        let report = ScanReport {
+            // Same string read from two functions, plus one other
+            hits: vec![
+                hit("API_KEY", 0x100, 1),
+                hit("API_KEY", 0x100, 2),
+                hit("DATABASE_URL", 0x200, 1),
+            ],
+            ..ScanReport::default()
+        };
+        assert_eq!(report.name_bytes(), 7 + 12);
+    }
+
+    #[test]
+    fn test_flag_suspicious_overlaps() {
+        let mut hits = vec![
+            hit("API_KEY", 0x100, 1),
+            hit("API_KEY_ID", 0x100, 2),
+            hit("DATABASE_URL", 0x200, 1),
+            hit("DATABASE_URL", 0x200, 3),
+        ];
+        flag_suspicious_overlaps(&mut hits);
+
+        assert!(hits[0].suspicious_overlap);
+        assert!(hits[1].suspicious_overlap);
+        // Same pointer, same length — consistent reads are not flagged
+        assert!(!hits[2].suspicious_overlap);
+        assert!(!hits[3].suspicious_overlap);
+
+        // Pointers in different core modules live in different address spaces
+        let mut hits = vec![hit("API_KEY", 0x100, 1), hit("API_KEY_ID", 0x100, 1)];
+        hits[1].module_index = 1;
+        flag_suspicious_overlaps(&mut hits);
+        assert!(hits.iter().all(|h| !h.suspicious_overlap));
+    }
+}
@@ -0,0 +1,43 @@
+//! Comparing detected variables with a declared list.
+
+/// How a module's detected variables line up with the set a deployment
+/// declares it provides. Every list is sorted and de-duplicated.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SchemaDiff {
+    /// Detected and declared.
+    pub satisfied: Vec<String>,
+    /// Declared, but the module doesn't read them.
+    pub missing_from_module: Vec<String>,
+    /// Read by the module, but not declared.
+    pub missing_from_schema: Vec<String>,
+}
+
+impl SchemaDiff {
+    /// Compare detected names (sorted and de-duplicated, as returned by
+    /// [`crate::scan_wasm_bytes`]) with the declared ones.
+    pub fn new(detected: &[String], expected: &[String]) -> Self {
+        let mut expected = expected.to_vec();
+        expected.sort();
+        expected.dedup();
+
+        let (satisfied, missing_from_schema) = detected
+            .iter()
+            .cloned()
+            .partition(|name| expected.binary_search(name).is_ok());
+        let missing_from_module = expected
+            .into_iter()
+            .filter(|name| detected.binary_search(name).is_err())
+            .collect();
+
+        Self {
+            satisfied,
+            missing_from_module,
+            missing_from_schema,
+        }
+    }
+
+    /// Whether the module reads exactly the declared variables.
+    pub fn is_exact_match(&self) -> bool {
+        self.missing_from_module.is_empty() && self.missing_from_schema.is_empty()
+    }
+}
@@ -0,0 +1,128 @@
+//! Confidence scoring for detected names.
+//!
+//! Each hit gets a 0–100 score from a small table of feature weights, and
+//! [`Confidence`] is derived from fixed score ranges. Tuning detection means
+//! adjusting [`WEIGHTS`] and the thresholds, not adding branches at the call
+//! sites.
+
+use crate::category::Category;
+use crate::report::Confidence;
+
+/// Points awarded per feature. The total is capped at 100.
+struct Weights {
+    /// The name was traced into an env/config import.
+    resolved_call: u8,
+    /// A segment is a typical config word (`URL`, `PORT`, `KEY`, ...).
+    keyword: u8,
+    /// Per `_` separator, up to `max_underscores`.
+    underscore: u8,
+    max_underscores: u8,
+    /// Length within [`TYPICAL_LEN`].
+    typical_length: u8,
+}
+
+const WEIGHTS: Weights = Weights {
+    resolved_call: 55,
+    keyword: 20,
+    underscore: 5,
+    max_underscores: 2,
+    typical_length: 10,
+};
+
+/// Names outside this length range are rarely real env vars.
+const TYPICAL_LEN: std::ops::RangeInclusive<usize> = 4..=40;
+
+/// Minimum scores for each confidence level. Anything traced into an env
+/// import with a typical length reaches `HIGH`; names without a resolved call
+/// can't reach `MEDIUM` on their own.
+const HIGH: u8 = 65;
+const MEDIUM: u8 = 50;
+
+/// Non-secret segments common in configuration variable names. Secret
+/// segments are covered by [`Category::Secret`].
+const KEYWORD_SEGMENTS: &[&str] = &[
+    "URL", "URI", "HOST", "PORT", "ADDR", "ENDPOINT", "CONFIG", "LEVEL", "DIR", "PATH", "MODE",
+    "ENV", "REGION", "BUCKET", "DATABASE", "DB",
+];
+
+/// Score a detected name and map the score to a [`Confidence`].
+/// `extra_keywords` count like [`KEYWORD_SEGMENTS`].
+pub fn classify_env_var(
+    name: &str,
+    resolved_call: bool,
+    extra_keywords: &[String],
+) -> (u8, Confidence) {
+    let has_keyword = name
+        .split('_')
+        .any(|segment| is_keyword_segment(segment, extra_keywords));
+    let underscores = name
+        .matches('_')
+        .count()
+        .min(WEIGHTS.max_underscores.into()) as u8;
+
+    let mut score = 0u8;
+    if resolved_call {
+        score += WEIGHTS.resolved_call;
+    }
+    if has_keyword {
+        score += WEIGHTS.keyword;
+    }
+    score += underscores * WEIGHTS.underscore;
+    if TYPICAL_LEN.contains(&name.len()) {
+        score += WEIGHTS.typical_length;
+    }
+    let score = score.min(100);
+
+    let confidence = if score >= HIGH {
+        Confidence::High
+    } else if score >= MEDIUM {
+        Confidence::Medium
+    } else {
+        Confidence::Low
+    };
+    (score, confidence)
+}
+
+/// Whether one `_`-separated segment of a name is a typical config word
+/// (case-insensitive): a secret segment, one of [`KEYWORD_SEGMENTS`] or one
+/// of `extra_keywords`.
+pub fn is_keyword_segment(segment: &str, extra_keywords: &[String]) -> bool {
+    Category::of(segment) == Category::Secret
+        || KEYWORD_SEGMENTS
+            .iter()
+            .copied()
+            .chain(extra_keywords.iter().map(String::as_str))
+            .any(|keyword| segment.eq_ignore_ascii_case(keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_env_var() {
+        assert_eq!(
+            classify_env_var("DATABASE_URL", true, &[]),
+            (90, Confidence::High)
+        );
+        assert_eq!(classify_env_var("PORT", true, &[]), (85, Confidence::High));
+        assert_eq!(
+            classify_env_var("mcp_servers", true, &[]),
+            (70, Confidence::High)
+        );
+        assert_eq!(classify_env_var("X", true, &[]), (55, Confidence::Medium));
+
+        assert_eq!(
+            classify_env_var("API_KEY", false, &[]),
+            (35, Confidence::Low)
+        );
+        assert_eq!(
+            classify_env_var("AWS_SECRET_ACCESS_KEY", false, &[]),
+            (40, Confidence::Low)
+        );
+
+        let extra = ["QUEUE".to_string()];
+        assert_eq!(classify_env_var("JOB_QUEUE", true, &[]).0, 70);
+        assert_eq!(classify_env_var("JOB_QUEUE", true, &extra).0, 90);
+    }
+}
@@ -5,23 +5,54 @@
 //! This enables extracting string arguments (pointer, length pairs) at
 //! env-related call sites.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
-use walrus::ir::{BinaryOp, Instr, InstrSeqId, Value};
-use walrus::{FunctionId, GlobalId, LocalId};
+use walrus::ir::{
+    BinaryOp, ExtendedLoad, Instr, InstrSeqId, InstrSeqType, LoadKind, LoadSimdKind, UnaryOp, Value,
+};
+use walrus::{DataId, FunctionId, GlobalId, LocalId, TypeId};
 
-use crate::strings::extract_string_args;
+use crate::analysis::EnvCallChains;
+use crate::config::ScanConfig;
+use crate::strings::{
+    extract_any_strings, extract_cstring_args, extract_default_arg, extract_interned_args,
+    extract_iovec_words, extract_string_args, read_default_cstring, read_i32_le, MemoryMap,
+    StringArg,
+};
+
+/// How many calls after a named env read may still be passing its default.
+const DEFAULT_WINDOW: u8 = 3;
 
 // ===== Value types =====
 
 /// A simplified WASM value for stack simulation.
 /// We only care about tracking i32 constants (pointers / lengths).
+///
+/// In memory64 modules pointers and lengths are i64, but static data still
+/// sits far below 4 GiB, so i64 values that fit in 32 bits are tracked as
+/// `Known` too (holding the `u32` bit pattern) and anything larger is
+/// `Unknown`.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SVal {
     Known(i32),
     Unknown,
 }
 
+impl SVal {
+    /// An i64 value in a memory64 module.
+    fn from_i64(v: i64) -> Self {
+        u32::try_from(v).map_or(Self::Unknown, |v| Self::Known(v.cast_signed()))
+    }
+
+    /// The value as an i64 address or length in a memory64 module.
+    fn as_u64(self) -> Option<i64> {
+        match self {
+            Self::Known(v) => Some(i64::from(v as u32)),
+            Self::Unknown => None,
+        }
+    }
+}
+
 // ===== Stack state =====
 
 /// Simulated WASM value stack and locals for taint analysis.
@@ -57,9 +88,33 @@ impl StackState {
             .unwrap_or(SVal::Unknown)
     }
 
+    /// The top `n` values (fewer if the stack is shallower), bottom first.
+    #[inline]
+    pub fn top(&self, n: usize) -> &[SVal] {
+        &self.stack[self.stack.len().saturating_sub(n)..]
+    }
+
     #[inline]
-    pub fn clear(&mut self) {
-        self.stack.clear();
+    pub fn pop_n(&mut self, n: usize) {
+        self.stack.truncate(self.stack.len().saturating_sub(n));
+    }
+
+    #[inline]
+    pub fn push_unknown(&mut self, n: usize) {
+        self.stack.extend(std::iter::repeat_n(SVal::Unknown, n));
+    }
+
+    /// Leave a block that started at stack height `base`: drop everything
+    /// above it and push the block's `results` values. They're taken from the
+    /// top of the stack if the body fell through, otherwise unknown.
+    pub fn end_block(&mut self, base: usize, results: usize, fell_through: bool) {
+        let values: Vec<SVal> = if fell_through {
+            (0..results).map(|i| self.peek(results - 1 - i)).collect()
+        } else {
+            vec![SVal::Unknown; results]
+        };
+        self.stack.truncate(base);
+        self.stack.extend(values);
     }
 
     #[inline]
@@ -75,24 +130,157 @@ impl StackState {
 
 // ===== Instruction walker =====
 
+/// Everything about the function being walked that stays fixed while
+/// walking it.
+pub struct WalkContext<'a> {
+    pub module: &'a walrus::Module,
+    pub func: &'a walrus::LocalFunction,
+    pub memory_map: &'a MemoryMap,
+    pub chains: &'a EnvCallChains,
+    pub config: &'a ScanConfig,
+    /// The module's memory is a memory64 one, addressed with i64 values.
+    pub memory64: bool,
+    /// The `fd_write` import, when scanning log messages
+    /// (see [`crate::ScanConfig::scan_logs`]).
+    pub log_sink: Option<FunctionId>,
+    /// Record every string passed to any call in [`CallSites::strings`],
+    /// not just names passed into the env call chain.
+    pub all_strings: bool,
+}
+
+impl WalkContext<'_> {
+    /// `(params, results)` of a function type.
+    fn arity(&self, ty: TypeId) -> (usize, usize) {
+        let ty = self.module.types.get(ty);
+        (ty.params().len(), ty.results().len())
+    }
+
+    /// The value a load of `kind` reads from `base + offset`, if the address is
+    /// known and backed by data segments. Covers full i32 loads, the
+    /// zero-extending 8- and 16-bit ones, and i64 loads in memory64 modules
+    /// whose value fits in 32 bits.
+    fn load(&self, kind: LoadKind, offset: u32, base: SVal) -> SVal {
+        let SVal::Known(base) = base else {
+            return SVal::Unknown;
+        };
+        let Some(addr) = (base as u32).checked_add(offset) else {
+            return SVal::Unknown;
+        };
+        let byte = |i: u32| {
+            let addr = addr.checked_add(i)?;
+            self.memory_map.get(addr)
+        };
+        let val = match kind {
+            LoadKind::I32 { atomic: false } => read_i32_le(self.memory_map, addr),
+            LoadKind::I32_8 {
+                kind: ExtendedLoad::ZeroExtend,
+            } => byte(0).map(i32::from),
+            LoadKind::I32_16 {
+                kind: ExtendedLoad::ZeroExtend,
+            } => byte(0)
+                .zip(byte(1))
+                .map(|(lo, hi)| i32::from(u16::from_le_bytes([lo, hi]))),
+            LoadKind::I64 { atomic: false } if self.memory64 => addr
+                .checked_add(4)
+                .and_then(|high| read_i32_le(self.memory_map, high))
+                .filter(|&high| high == 0)
+                .and_then(|_| read_i32_le(self.memory_map, addr)),
+            _ => None,
+        };
+        val.map_or(SVal::Unknown, SVal::Known)
+    }
+
+    /// `(params, results)` of a block, loop or if.
+    fn block_arity(&self, seq_id: InstrSeqId) -> (usize, usize) {
+        match self.func.block(seq_id).ty {
+            InstrSeqType::Simple(result) => (0, usize::from(result.is_some())),
+            InstrSeqType::MultiValue(ty) => self.arity(ty),
+        }
+    }
+}
+
+/// What a walk found at env-related call sites.
+#[derive(Default)]
+pub struct CallSites {
+    /// Env var names recovered from call arguments.
+    pub args: Vec<StringArg>,
+    /// Every call into the env call chain that takes arguments.
+    pub env_calls: Vec<EnvCall>,
+    /// Strings read from call arguments that failed validation, with the
+    /// reason.
+    pub rejected: Vec<(String, &'static str)>,
+    /// Env-like words in messages passed to `fd_write`.
+    pub logged: Vec<StringArg>,
+    /// `memory.init` and `memory.fill` writes whose operands were all known,
+    /// in the order they were walked.
+    pub memory_writes: Vec<MemoryWrite>,
+    /// Every string passed to a call, when [`WalkContext::all_strings`] is set.
+    pub strings: Vec<String>,
+    /// Fallback values for names in `args`, as `(index into args, value)`.
+    pub defaults: Vec<(usize, String)>,
+    /// Names from the last env read that have no default yet, and how many
+    /// more calls may still supply one.
+    awaiting_default: Option<(std::ops::Range<usize>, u8)>,
+}
+
+impl CallSites {
+    /// Attach `value` as the default of the names still awaiting one.
+    fn found_default(&mut self, value: &str) {
+        if let Some((names, _)) = self.awaiting_default.take() {
+            self.defaults
+                .extend(names.map(|index| (index, value.to_string())));
+        }
+    }
+}
+
+/// A `memory.init` copying `len` bytes from `offset` in passive segment
+/// `data` to address `dest`.
+pub struct SegmentCopy {
+    pub data: DataId,
+    pub dest: u32,
+    pub offset: u32,
+    pub len: u32,
+}
+
+/// A bulk write to linear memory found while walking a function.
+pub enum MemoryWrite {
+    /// Passive segment bytes copied in.
+    Init(SegmentCopy),
+    /// `len` bytes at `dest` overwritten by `memory.fill`. Whatever a data
+    /// segment put there is gone; the fill value isn't tracked.
+    Fill { dest: u32, len: u32 },
+}
+
+/// A call into the env call chain.
+pub struct EnvCall {
+    pub callee: FunctionId,
+    /// An argument was a valid env var name.
+    pub named: bool,
+    /// An argument could be read as a string at all (possibly a filtered one,
+    /// like `RUST_BACKTRACE`).
+    pub read: bool,
+}
+
 /// Recursively walk an instruction sequence, simulating the stack.
 /// Only captures strings at call sites to functions in the env call chain.
+///
+/// Returns `false` if the sequence ended in a branch, return or trap rather
+/// than falling through, in which case the stack contents are meaningless.
 #[allow(clippy::too_many_lines)]
 pub fn walk_seq(
-    func: &walrus::LocalFunction,
+    ctx: &WalkContext,
     seq_id: InstrSeqId,
     state: &mut StackState,
     globals: &mut HashMap<GlobalId, i32>,
-    memory_map: &HashMap<u32, u8>,
-    env_call_chain: &HashSet<FunctionId>,
-    env_vars: &mut HashSet<String>,
-) {
-    let seq = func.block(seq_id);
+    found: &mut CallSites,
+) -> bool {
+    let seq = ctx.func.block(seq_id);
     for (instr, _loc) in &seq.instrs {
         match instr {
             // Constants
             Instr::Const(c) => match c.value {
                 Value::I32(v) => state.push(SVal::Known(v)),
+                Value::I64(v) if ctx.memory64 => state.push(SVal::from_i64(v)),
                 _ => state.push(SVal::Unknown),
             },
 
@@ -134,27 +322,22 @@ pub fn walk_seq(
             Instr::Binop(b) => {
                 let rhs = state.pop();
                 let lhs = state.pop();
-                match b.op {
-                    BinaryOp::I32Add => {
-                        state.push(match (lhs, rhs) {
-                            (SVal::Known(x), SVal::Known(y)) => SVal::Known(x.wrapping_add(y)),
-                            _ => SVal::Unknown,
-                        });
-                    }
-                    BinaryOp::I32Sub => {
-                        state.push(match (lhs, rhs) {
-                            (SVal::Known(x), SVal::Known(y)) => SVal::Known(x.wrapping_sub(y)),
-                            _ => SVal::Unknown,
-                        });
-                    }
-                    _ => state.push(SVal::Unknown),
-                }
+                state.push(fold_binop(ctx, b.op, lhs, rhs));
             }
 
-            // Unary operations / Memory loads — pop one, push unknown
-            Instr::Unop(..) | Instr::Load(..) => {
-                state.pop();
-                state.push(SVal::Unknown);
+            // Loads from a known address resolve against the data segments,
+            // e.g. the ptr/len fields of a fat pointer stored in a static:
+            // `i32.load offset=0 (base)` and `i32.load offset=4 (base)`
+            Instr::Load(load) => {
+                let base = state.pop();
+                state.push(ctx.load(load.kind, load.arg.offset, base));
+            }
+
+            // Unary operations: width conversions keep known values that
+            // fit, anything else pushes unknown
+            Instr::Unop(u) => {
+                let val = state.pop();
+                state.push(fold_unop(u.op, val));
             }
 
             // Memory stores
@@ -163,18 +346,49 @@ pub fn walk_seq(
                 state.pop();
             }
 
+            // Passive data copied into memory at startup, e.g. a string
+            // table staged by `__wasm_init_memory`
+            Instr::MemoryInit(init) => {
+                let len = state.pop();
+                let offset = state.pop();
+                let dest = state.pop();
+                if let (SVal::Known(dest), SVal::Known(offset), SVal::Known(len)) =
+                    (dest, offset, len)
+                {
+                    found.memory_writes.push(MemoryWrite::Init(SegmentCopy {
+                        data: init.data,
+                        dest: dest as u32,
+                        offset: offset as u32,
+                        len: len as u32,
+                    }));
+                }
+            }
+
+            // Zeroing or poisoning a region invalidates the data there
+            Instr::MemoryFill(_) => {
+                let len = state.pop();
+                state.pop();
+                let dest = state.pop();
+                if let (SVal::Known(dest), SVal::Known(len)) = (dest, len) {
+                    found.memory_writes.push(MemoryWrite::Fill {
+                        dest: dest as u32,
+                        len: len as u32,
+                    });
+                }
+            }
+
             // Function calls — the core of taint analysis
             Instr::Call(c) => {
-                if env_call_chain.contains(&c.func) {
-                    extract_string_args(state, memory_map, env_vars);
-                }
-                state.clear();
-                state.push(SVal::Unknown);
+                let (params, results) = inspect_call(ctx, c.func, state, found);
+                state.pop_n(params);
+                state.push_unknown(results);
             }
-            Instr::CallIndirect(..) => {
-                // Can't resolve indirect calls statically
-                state.clear();
-                state.push(SVal::Unknown);
+            Instr::CallIndirect(ci) => {
+                // Can't resolve indirect calls statically, but the type still
+                // tells us how the stack changes
+                let (params, results) = ctx.arity(ci.ty);
+                state.pop_n(params + 1); // + table index
+                state.push_unknown(results);
             }
 
             // Stack manipulation
@@ -182,68 +396,267 @@ pub fn walk_seq(
                 state.pop(); // condition
                 let b = state.pop();
                 let a = state.pop();
+                // `getenv("X") ?: "default"` selects between the result and a
+                // string constant
+                if found.awaiting_default.is_some() {
+                    if let Some(value) = [a, b]
+                        .into_iter()
+                        .find_map(|val| read_default_cstring(val, ctx.memory_map, ctx.config))
+                    {
+                        found.found_default(&value);
+                    }
+                }
                 state.push(if a == b { a } else { SVal::Unknown });
             }
 
-            // Control flow — walk all branches
+            // Control flow — walk all branches. Blocks share the outer state
+            // so local writes stay visible after them; the stack is then
+            // reset to the block's declared results.
             Instr::Block(block) => {
-                walk_seq(
-                    func,
-                    block.seq,
-                    state,
-                    globals,
-                    memory_map,
-                    env_call_chain,
-                    env_vars,
-                );
+                let (params, results) = ctx.block_arity(block.seq);
+                let base = state.stack.len().saturating_sub(params);
+                let fell_through = walk_seq(ctx, block.seq, state, globals, found);
+                state.end_block(base, results, fell_through);
             }
 
             Instr::Loop(lp) => {
-                walk_seq(
-                    func,
-                    lp.seq,
-                    state,
-                    globals,
-                    memory_map,
-                    env_call_chain,
-                    env_vars,
-                );
+                let (params, results) = ctx.block_arity(lp.seq);
+                let base = state.stack.len().saturating_sub(params);
+                let fell_through = walk_seq(ctx, lp.seq, state, globals, found);
+                state.end_block(base, results, fell_through);
             }
 
             Instr::IfElse(ie) => {
                 state.pop(); // condition
+                let (params, results) = ctx.block_arity(ie.consequent);
+                let base = state.stack.len().saturating_sub(params);
 
                 let mut then_state = state.clone();
                 let mut else_state = state.clone();
-
-                walk_seq(
-                    func,
+                // A `global.set` in one branch isn't seen by the other
+                let mut then_globals = globals.clone();
+                let mut else_globals = globals.clone();
+                let then_fell = walk_seq(
+                    ctx,
                     ie.consequent,
                     &mut then_state,
-                    globals,
-                    memory_map,
-                    env_call_chain,
-                    env_vars,
+                    &mut then_globals,
+                    found,
                 );
-                walk_seq(
-                    func,
+                let else_fell = walk_seq(
+                    ctx,
                     ie.alternative,
                     &mut else_state,
-                    globals,
-                    memory_map,
-                    env_call_chain,
-                    env_vars,
+                    &mut else_globals,
+                    found,
                 );
 
-                state.clear();
-                state.push(SVal::Unknown);
+                // Likewise, a global stays known only if the branches agree on it
+                *globals = match (then_fell, else_fell) {
+                    (true, false) => then_globals,
+                    (false, true) => else_globals,
+                    _ => {
+                        then_globals.retain(|global, val| else_globals.get(global) == Some(val));
+                        then_globals
+                    }
+                };
+
+                // Keep a result only if every branch that falls through agrees on it
+                let merged: Vec<SVal> = (0..results)
+                    .map(|i| {
+                        let depth = results - 1 - i;
+                        match (then_fell, else_fell) {
+                            (true, true) if then_state.peek(depth) == else_state.peek(depth) => {
+                                then_state.peek(depth)
+                            }
+                            (true, false) => then_state.peek(depth),
+                            (false, true) => else_state.peek(depth),
+                            _ => SVal::Unknown,
+                        }
+                    })
+                    .collect();
+                state.stack.truncate(base);
+                state.stack.extend(merged);
             }
 
-            Instr::Br(..) | Instr::BrTable(..) | Instr::Return(..) | Instr::Unreachable(..) => {
-                return;
+            // A tail call ends the function, but its arguments are inspected
+            // like any other call's
+            Instr::ReturnCall(rc) => {
+                inspect_call(ctx, rc.func, state, found);
+                return false;
             }
 
-            _ => {}
+            Instr::Br(..)
+            | Instr::BrTable(..)
+            | Instr::Return(..)
+            | Instr::ReturnCallIndirect(..)
+            | Instr::Unreachable(..) => {
+                return false;
+            }
+
+            // Everything else only matters for its effect on the stack height,
+            // which has to stay right for later (ptr, len) reads to line up
+            _ => {
+                let (pops, pushes) = stack_effect(instr);
+                state.pop_n(pops);
+                state.push_unknown(pushes);
+            }
+        }
+    }
+    true
+}
+
+/// `(operands popped, results pushed)` of an instruction [`walk_seq`] has
+/// no special handling for.
+fn stack_effect(instr: &Instr) -> (usize, usize) {
+    match instr {
+        Instr::MemorySize(..) | Instr::TableSize(..) | Instr::RefNull(..) | Instr::RefFunc(..) => {
+            (0, 1)
+        }
+        Instr::MemoryGrow(..) | Instr::TableGet(..) | Instr::RefIsNull(..) => (1, 1),
+        Instr::TableSet(..) => (2, 0),
+        Instr::AtomicRmw(..)
+        | Instr::AtomicNotify(..)
+        | Instr::TableGrow(..)
+        | Instr::I8x16Swizzle(..)
+        | Instr::I8x16Shuffle(..) => (2, 1),
+        Instr::Cmpxchg(..) | Instr::AtomicWait(..) | Instr::V128Bitselect(..) => (3, 1),
+        Instr::MemoryCopy(..)
+        | Instr::TableFill(..)
+        | Instr::TableInit(..)
+        | Instr::TableCopy(..) => (3, 0),
+        Instr::LoadSimd(load) => match load.kind {
+            LoadSimdKind::V128Load8Lane(_)
+            | LoadSimdKind::V128Load16Lane(_)
+            | LoadSimdKind::V128Load32Lane(_)
+            | LoadSimdKind::V128Load64Lane(_) => (2, 1),
+            LoadSimdKind::V128Store8Lane(_)
+            | LoadSimdKind::V128Store16Lane(_)
+            | LoadSimdKind::V128Store32Lane(_)
+            | LoadSimdKind::V128Store64Lane(_) => (2, 0),
+            _ => (1, 1),
+        },
+        _ => (0, 0),
+    }
+}
+
+/// Look for the default of the last env read among the arguments of a call
+/// shortly after it, e.g. the literal `unwrap_or("8080")` copies out.
+fn await_default(ctx: &WalkContext, args: &[SVal], found: &mut CallSites) {
+    let Some((_, calls_left)) = &mut found.awaiting_default else {
+        return;
+    };
+    *calls_left -= 1;
+    let expired = *calls_left == 0;
+    if let Some(value) = extract_default_arg(args, ctx.memory_map, ctx.config) {
+        found.found_default(&value);
+    } else if expired {
+        found.awaiting_default = None;
+    }
+}
+
+/// Evaluate an integer binary operation on known operands, e.g. the
+/// `base + index * stride` LLVM emits to index a string table. Arithmetic
+/// wraps and shift counts are taken modulo 32, as in WASM.
+fn fold_binop(ctx: &WalkContext, op: BinaryOp, lhs: SVal, rhs: SVal) -> SVal {
+    if ctx.memory64 && matches!(op, BinaryOp::I64Add | BinaryOp::I64Sub) {
+        return match (lhs.as_u64(), rhs.as_u64()) {
+            (Some(x), Some(y)) if matches!(op, BinaryOp::I64Add) => SVal::from_i64(x + y),
+            (Some(x), Some(y)) => SVal::from_i64(x - y),
+            _ => SVal::Unknown,
+        };
+    }
+    let (SVal::Known(x), SVal::Known(y)) = (lhs, rhs) else {
+        return SVal::Unknown;
+    };
+    SVal::Known(match op {
+        BinaryOp::I32Add => x.wrapping_add(y),
+        BinaryOp::I32Sub => x.wrapping_sub(y),
+        BinaryOp::I32Mul => x.wrapping_mul(y),
+        BinaryOp::I32Shl => x.wrapping_shl(y as u32),
+        BinaryOp::I32ShrS => x.wrapping_shr(y as u32),
+        BinaryOp::I32ShrU => (x as u32).wrapping_shr(y as u32).cast_signed(),
+        BinaryOp::I32And => x & y,
+        BinaryOp::I32Or => x | y,
+        BinaryOp::I32Xor => x ^ y,
+        _ => return SVal::Unknown,
+    })
+}
+
+/// Evaluate an integer width conversion on a known operand, e.g. the
+/// `i64.extend_i32_u` / `i32.wrap_i64` pair LLVM puts around a pointer passed
+/// through an i64. An i64 result that doesn't fit in 32 bits (a negative
+/// sign extension) is unknown, like any other large i64.
+fn fold_unop(op: UnaryOp, val: SVal) -> SVal {
+    let SVal::Known(v) = val else {
+        return SVal::Unknown;
+    };
+    match op {
+        // i64 operands already hold a value that fits in 32 bits
+        UnaryOp::I64ExtendUI32 | UnaryOp::I32WrapI64 => val,
+        UnaryOp::I32Extend8S => SVal::Known(i32::from(v as i8)),
+        UnaryOp::I32Extend16S => SVal::Known(i32::from(v as i16)),
+        UnaryOp::I64ExtendSI32 | UnaryOp::I64Extend32S => SVal::from_i64(i64::from(v)),
+        UnaryOp::I64Extend8S => SVal::from_i64(i64::from(v as i8)),
+        UnaryOp::I64Extend16S => SVal::from_i64(i64::from(v as i16)),
+        _ => SVal::Unknown,
+    }
+}
+
+/// Record any env var names passed to a direct call of `callee`, leaving the
+/// stack untouched. Returns the callee's `(params, results)`.
+fn inspect_call(
+    ctx: &WalkContext,
+    callee: FunctionId,
+    state: &StackState,
+    found: &mut CallSites,
+) -> (usize, usize) {
+    let (params, results) = ctx.arity(ctx.module.funcs.get(callee).ty());
+    let args = state.top(params);
+    let before = found.args.len();
+    let mut env_call = None;
+    if let Some(&sink) = ctx.chains.ptr_len.get(&callee) {
+        let mut read = extract_string_args(args, ctx.memory_map, ctx.config, sink, found);
+        // No usable (ptr, len) pair; the name may be an offset into an
+        // interned strings blob
+        if ctx.config.interned_strings && found.args.len() == before {
+            read |= extract_interned_args(args, ctx.memory_map, ctx.config, sink, found);
+        }
+        // Or a C string handed to a wrapper that measures it first
+        if ctx.config.c_strings && found.args.len() == before {
+            let known: Vec<SVal> = args
+                .iter()
+                .copied()
+                .filter(|val| matches!(val, SVal::Known(_)))
+                .collect();
+            if known.len() == 1 {
+                read |= extract_cstring_args(&known, ctx.memory_map, ctx.config, sink, found);
+            }
         }
+        env_call = Some(read);
+    }
+    if let Some(&sink) = ctx.chains.c_str.get(&callee) {
+        let read = extract_cstring_args(args, ctx.memory_map, ctx.config, sink, found);
+        env_call = Some(env_call.unwrap_or(false) || read);
+    }
+    if ctx.log_sink == Some(callee) {
+        extract_iovec_words(args, ctx.memory_map, ctx.config, callee, found);
+    }
+    if ctx.all_strings {
+        extract_any_strings(args, ctx.memory_map, ctx.config, found);
+    }
+    if found.args.len() > before {
+        found.awaiting_default = Some((before..found.args.len(), DEFAULT_WINDOW));
+    } else if env_call.is_none() {
+        await_default(ctx, args, found);
+    }
+    // Only calls that take arguments can be passing a name
+    if let Some(read) = env_call.filter(|_| params > 0) {
+        found.env_calls.push(EnvCall {
+            callee,
+            named: found.args.len() > before,
+            read,
+        });
     }
+    (params, results)
 }
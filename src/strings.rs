@@ -6,94 +6,598 @@
 //! - Validating that extracted strings look like real env var names
 //! - Building the memory map and collecting global constants
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 use walrus::ir::Value;
-use walrus::{ConstExpr, GlobalId, GlobalKind};
+use walrus::{ConstExpr, FunctionId, GlobalId, GlobalKind, ImportKind};
 
-use crate::stack::{SVal, StackState};
+use crate::config::ScanConfig;
+use crate::report::NameRule;
+use crate::score::is_keyword_segment;
+use crate::stack::{CallSites, MemoryWrite, SVal};
 
 // ===== Memory map & globals =====
 
-/// Build a memory map from data segments (offset → byte).
+/// The initialized bytes of linear memory, as non-overlapping runs sorted by
+/// address.
+///
+/// Adjacent and overlapping writes are merged, so every run is a maximal
+/// stretch of initialized bytes: a string is readable exactly when it fits
+/// inside one run, and reads slice the run directly.
+#[derive(Debug, Default)]
+pub struct MemoryMap {
+    segments: Vec<DataSegment>,
+    /// Which data segment each write came from, in write order.
+    origins: Vec<Origin>,
+}
+
+/// `len` bytes at `start` written from `data_offset` on in the data segment
+/// with index `data_index`.
+#[derive(Debug)]
+struct Origin {
+    start: u32,
+    len: usize,
+    data_index: usize,
+    data_offset: usize,
+}
+
+/// One run of initialized bytes starting at `start`.
+#[derive(Debug)]
+struct DataSegment {
+    start: u32,
+    bytes: Vec<u8>,
+}
+
+impl DataSegment {
+    /// One past the last address, which can be `2^32`.
+    fn end(&self) -> u64 {
+        u64::from(self.start) + self.bytes.len() as u64
+    }
+}
+
+impl MemoryMap {
+    /// [`Self::write`] bytes taken from `data_offset` on in the data segment
+    /// with index `data_index`, remembering where they came from.
+    pub fn write_data(&mut self, addr: u32, data_index: usize, data_offset: usize, bytes: &[u8]) {
+        self.write(addr, bytes);
+        self.origins.push(Origin {
+            start: addr,
+            len: bytes.len(),
+            data_index,
+            data_offset,
+        });
+    }
+
+    /// The data segment index and the offset within its bytes that the byte
+    /// at `addr` was last written from, if it came from a data segment.
+    pub fn origin(&self, addr: u32) -> Option<(usize, usize)> {
+        self.origins
+            .iter()
+            .rev()
+            .find(|origin| {
+                addr >= origin.start && u64::from(addr - origin.start) < origin.len as u64
+            })
+            .map(|origin| {
+                (
+                    origin.data_index,
+                    origin.data_offset + (addr - origin.start) as usize,
+                )
+            })
+    }
+
+    /// Write `bytes` at `addr`, over anything already there. Bytes past the
+    /// end of the 32-bit address space are dropped.
+    pub fn write(&mut self, addr: u32, bytes: &[u8]) {
+        let room = (1u64 << 32) - u64::from(addr);
+        let bytes = &bytes[..bytes.len().min(room as usize)];
+        if bytes.is_empty() {
+            return;
+        }
+        let start = u64::from(addr);
+        let end = start + bytes.len() as u64;
+
+        // Runs this write overlaps or touches get merged into one
+        let lo = self.segments.partition_point(|seg| seg.end() < start);
+        let hi = self
+            .segments
+            .partition_point(|seg| u64::from(seg.start) <= end);
+        let mut merged = DataSegment {
+            start: addr,
+            bytes: Vec::new(),
+        };
+        if let Some(first) = self.segments.get(lo).filter(|_| lo < hi) {
+            if first.start < addr {
+                merged.start = first.start;
+                merged
+                    .bytes
+                    .extend_from_slice(&first.bytes[..(addr - first.start) as usize]);
+            }
+        }
+        merged.bytes.extend_from_slice(bytes);
+        if let Some(last) = hi.checked_sub(1).filter(|&i| i >= lo) {
+            let last = &self.segments[last];
+            if last.end() > end {
+                let skip = (end - u64::from(last.start)) as usize;
+                merged.bytes.extend_from_slice(&last.bytes[skip..]);
+            }
+        }
+        self.segments.splice(lo..hi, [merged]);
+    }
+
+    /// Forget the `len` bytes at `addr`, splitting the runs around them.
+    pub fn clear(&mut self, addr: u32, len: u32) {
+        let start = u64::from(addr);
+        let end = start + u64::from(len);
+        let lo = self.segments.partition_point(|seg| seg.end() <= start);
+        let hi = self
+            .segments
+            .partition_point(|seg| u64::from(seg.start) < end);
+        if lo >= hi {
+            return;
+        }
+        let mut kept = Vec::new();
+        let first = &self.segments[lo];
+        if first.start < addr {
+            kept.push(DataSegment {
+                start: first.start,
+                bytes: first.bytes[..(addr - first.start) as usize].to_vec(),
+            });
+        }
+        let last = &self.segments[hi - 1];
+        if last.end() > end {
+            let skip = (end - u64::from(last.start)) as usize;
+            kept.push(DataSegment {
+                // Below the end of `last`, so within 32 bits
+                start: end as u32,
+                bytes: last.bytes[skip..].to_vec(),
+            });
+        }
+        self.segments.splice(lo..hi, kept);
+    }
+
+    /// The byte at `addr`, if initialized.
+    pub fn get(&self, addr: u32) -> Option<u8> {
+        self.prefix(addr, 1).map(|bytes| bytes[0])
+    }
+
+    /// The `len` bytes at `addr`, if all of them are initialized.
+    pub fn slice(&self, addr: u32, len: u32) -> Option<&[u8]> {
+        self.prefix(addr, len)
+            .filter(|bytes| bytes.len() == len as usize)
+    }
+
+    /// Up to `max_len` bytes at `addr`, stopping at the first uninitialized
+    /// one. `None` if `addr` itself isn't initialized.
+    fn prefix(&self, addr: u32, max_len: u32) -> Option<&[u8]> {
+        let i = self
+            .segments
+            .partition_point(|seg| seg.start <= addr)
+            .checked_sub(1)?;
+        let seg = &self.segments[i];
+        let offset = (addr - seg.start) as usize;
+        let rest = seg.bytes.get(offset..).filter(|rest| !rest.is_empty())?;
+        Some(&rest[..rest.len().min(max_len as usize)])
+    }
+}
+
+/// Build a memory map from data segments.
 ///
 /// WASM data segments define the initial memory contents. We use them
 /// to read string literals that are referenced as (ptr, len) pairs.
-pub fn build_memory_map(module: &walrus::Module) -> HashMap<u32, u8> {
-    let mut map = HashMap::new();
+/// Segments are applied in the order they appear in the module, so where
+/// two overlap the later one wins, as at instantiation.
+pub fn build_memory_map(module: &walrus::Module) -> MemoryMap {
+    let mut map = MemoryMap::default();
+    for (data_index, base, bytes) in active_data_segments(module) {
+        map.write_data(base, data_index, 0, bytes);
+    }
+    map
+}
+
+/// `(index, address, bytes)` of every active data segment whose address is
+/// known, in module order.
+pub fn active_data_segments(module: &walrus::Module) -> Vec<(usize, u32, &[u8])> {
+    let memory_base = find_memory_base(module);
+    let mut segments = Vec::new();
     for data in module.data.iter() {
-        if let walrus::DataKind::Active {
-            offset: ConstExpr::Value(Value::I32(base_offset)),
-            ..
-        } = &data.kind
-        {
-            let base = *base_offset as u32;
-            for (i, &byte) in data.value.iter().enumerate() {
-                map.insert(base + i as u32, byte);
+        let base = match &data.kind {
+            walrus::DataKind::Active {
+                offset: ConstExpr::Value(Value::I32(base_offset)),
+                ..
+            } => *base_offset as u32,
+            // memory64 offsets, kept only within the 32-bit map
+            walrus::DataKind::Active {
+                offset: ConstExpr::Value(Value::I64(base_offset)),
+                ..
+            } => match u32::try_from(*base_offset) {
+                Ok(base) => base,
+                Err(_) => continue,
+            },
+            // Placed at the (assumed zero) memory base of a PIC module
+            walrus::DataKind::Active {
+                offset: ConstExpr::Global(global),
+                ..
+            } if Some(*global) == memory_base => 0,
+            _ => continue,
+        };
+        segments.push((data.id().index(), base, data.value.as_slice()));
+    }
+    segments
+}
+
+/// Replay startup writes on the memory map, in order: passive segment bytes
+/// copied in by `memory.init` are written, and regions overwritten by
+/// `memory.fill` are cleared, so no string is read from them. Copies that run
+/// past the end of their segment are skipped, since they would trap.
+pub fn apply_memory_writes(module: &walrus::Module, map: &mut MemoryMap, writes: &[MemoryWrite]) {
+    for write in writes {
+        let copy = match write {
+            MemoryWrite::Init(copy) => copy,
+            MemoryWrite::Fill { dest, len } => {
+                map.clear(*dest, *len);
+                continue;
             }
+        };
+        let data = module.data.get(copy.data);
+        if !matches!(data.kind, walrus::DataKind::Passive) {
+            continue;
         }
+        let start = copy.offset as usize;
+        let Some(bytes) = start
+            .checked_add(copy.len as usize)
+            .and_then(|end| data.value.get(start..end))
+        else {
+            continue;
+        };
+        map.write_data(copy.dest, copy.data.index(), start, bytes);
     }
-    map
 }
 
 /// Collect global constant values (`GlobalId` → i32).
 pub fn collect_globals(module: &walrus::Module) -> HashMap<GlobalId, i32> {
     let mut globals = HashMap::new();
     for global in module.globals.iter() {
-        if let GlobalKind::Local(ConstExpr::Value(Value::I32(val))) = &global.kind {
-            globals.insert(global.id(), *val);
+        match &global.kind {
+            GlobalKind::Local(ConstExpr::Value(Value::I32(val))) => {
+                globals.insert(global.id(), *val);
+            }
+            GlobalKind::Local(ConstExpr::Value(Value::I64(val))) if is_memory64(module) => {
+                if let Ok(val) = u32::try_from(*val) {
+                    globals.insert(global.id(), val.cast_signed());
+                }
+            }
+            _ => {}
         }
     }
+    if let Some(memory_base) = find_memory_base(module) {
+        globals.insert(memory_base, 0);
+    }
     globals
 }
 
+/// Whether the module's memory is indexed with i64 (the memory64 proposal),
+/// so pointers and lengths are passed as i64 values.
+pub fn is_memory64(module: &walrus::Module) -> bool {
+    module
+        .memories
+        .iter()
+        .next()
+        .is_some_and(|memory| memory.memory64)
+}
+
+/// The imported `__memory_base` global of a position-independent module.
+///
+/// PIC code addresses static data as `__memory_base + offset`, with the base
+/// only chosen by the loader. Data segments are placed relative to the same
+/// global, so treating it as 0 lines code addresses up with the memory map.
+fn find_memory_base(module: &walrus::Module) -> Option<GlobalId> {
+    module.imports.iter().find_map(|import| match import.kind {
+        ImportKind::Global(global) if import.name == "__memory_base" => Some(global),
+        _ => None,
+    })
+}
+
 // ===== String extraction =====
 
-/// Extract all valid string arguments from the stack.
+/// A validated string argument recovered from a call site.
+#[derive(Clone, Debug)]
+pub struct StringArg {
+    pub ptr: u32,
+    pub len: u32,
+    pub value: String,
+    /// The env-related import the call ultimately reaches.
+    pub sink: FunctionId,
+    /// The naming rule the value passed.
+    pub rule: NameRule,
+}
+
+/// Record every string a consecutive (ptr, len) argument pair reads as,
+/// without any env var name validation.
+pub fn extract_any_strings(
+    args: &[SVal],
+    memory_map: &MemoryMap,
+    config: &ScanConfig,
+    found: &mut CallSites,
+) {
+    for pair in args.windows(2) {
+        if let [SVal::Known(ptr), SVal::Known(len)] = *pair {
+            if let Some(s) = read_name(memory_map, config, ptr as u32, len as u32) {
+                found.strings.push(s);
+            }
+        }
+    }
+}
+
+/// The first string a consecutive (ptr, len) argument pair reads as that
+/// could be a variable's default value; see [`is_plausible_default`].
+pub fn extract_default_arg(
+    args: &[SVal],
+    memory_map: &MemoryMap,
+    config: &ScanConfig,
+) -> Option<String> {
+    args.windows(2).find_map(|pair| match *pair {
+        [SVal::Known(ptr), SVal::Known(len)] => {
+            let (ptr, len) = (ptr as u32, len as u32);
+            if ptr == 0 || ptr < config.min_ptr || len > config.max_string_len {
+                return None;
+            }
+            read_string(memory_map, ptr, len).filter(|s| is_plausible_default(s))
+        }
+        _ => None,
+    })
+}
+
+/// [`extract_default_arg`] for a bare pointer to a NUL-terminated string.
+pub fn read_default_cstring(
+    val: SVal,
+    memory_map: &MemoryMap,
+    config: &ScanConfig,
+) -> Option<String> {
+    let SVal::Known(ptr) = val else {
+        return None;
+    };
+    let ptr = ptr as u32;
+    if ptr == 0 || ptr < config.min_ptr {
+        return None;
+    }
+    read_cstring(memory_map, ptr, config.max_string_len).filter(|s| is_plausible_default(s))
+}
+
+/// Default values are short settings like `8080`, `info` or
+/// `http://localhost:3000`. Anything with whitespace is more likely a
+/// message, e.g. the one passed to `expect` when the variable is missing.
+fn is_plausible_default(s: &str) -> bool {
+    !s.is_empty() && !s.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+/// Extract all valid string arguments from a call's arguments.
 /// Scans for consecutive (Known(ptr), Known(len)) pairs that point to
-/// valid strings in the memory map.
+/// valid strings in the memory map. Some codegen pushes the length first, so
+/// if no pair reads as (ptr, len), pairs are tried as (len, ptr), where only
+/// a `SCREAMING_SNAKE_CASE` env var name is accepted.
+///
+/// Returns whether any argument pointed at a readable string, valid env var
+/// name or not.
 pub fn extract_string_args(
-    state: &StackState,
-    memory_map: &HashMap<u32, u8>,
-    env_vars: &mut HashSet<String>,
-) {
-    let stack = &state.stack;
-    if stack.len() < 2 {
-        return;
+    args: &[SVal],
+    memory_map: &MemoryMap,
+    config: &ScanConfig,
+    sink: FunctionId,
+    found: &mut CallSites,
+) -> bool {
+    if args.len() < 2 {
+        return false;
+    }
+
+    // Interpret as unsigned — a negative i32 is a valid large u32 address
+    let pair = |i: usize| match (args[i], args[i + 1]) {
+        (SVal::Known(first), SVal::Known(second)) => Some((first as u32, second as u32)),
+        _ => None,
+    };
+
+    // Scan consecutive argument pairs as potential (ptr, len)
+    let mut read_any = false;
+    for i in 0..args.len() - 1 {
+        let Some((ptr, len)) = pair(i) else {
+            continue;
+        };
+        if let Some(s) = read_name(memory_map, config, ptr, len) {
+            read_any = true;
+            record_candidate(s, ptr, len, config, sink, found);
+        }
+    }
+    if read_any {
+        return true;
     }
 
-    // Scan consecutive pairs on the stack as potential (ptr, len)
-    for i in 0..stack.len() - 1 {
-        if let (SVal::Known(ptr), SVal::Known(len)) = (stack[i], stack[i + 1]) {
-            // Interpret as unsigned — a negative i32 is a valid large u32 address
+    // No (ptr, len) pair at all: try (len, ptr). Most integer pairs read as
+    // *something* this way round, so only a SCREAMING_SNAKE_CASE name counts.
+    for i in 0..args.len() - 1 {
+        let Some((len, ptr)) = pair(i) else {
+            continue;
+        };
+        if let Some(s) = read_name(memory_map, config, ptr, len) {
+            if untraced_name_rule(&s, config).is_some() {
+                read_any = true;
+                record_candidate(s, ptr, len, config, sink, found);
+            }
+        }
+    }
+    read_any
+}
+
+/// Read `(ptr, len)` as a string if the pointer and length are plausible for
+/// a name.
+fn read_name(memory_map: &MemoryMap, config: &ScanConfig, ptr: u32, len: u32) -> Option<String> {
+    if ptr > 0 && ptr >= config.min_ptr && (1..=config.max_string_len).contains(&len) {
+        read_string(memory_map, ptr, len).or_else(|| {
+            config
+                .partial_strings
+                .then(|| read_prefix(memory_map, ptr, len))
+                .flatten()
+        })
+    } else {
+        None
+    }
+}
+
+/// Extract NUL-terminated string arguments from a call's arguments.
+/// Used at call sites reaching a C `getenv`, where the name is passed as a
+/// single pointer with no length operand. Returns whether any argument
+/// pointed at a readable string.
+pub fn extract_cstring_args(
+    args: &[SVal],
+    memory_map: &MemoryMap,
+    config: &ScanConfig,
+    sink: FunctionId,
+    found: &mut CallSites,
+) -> bool {
+    extract_nul_terminated(args, memory_map, config, sink, found, |_| true)
+}
+
+/// Extract names from bare pointers into a NUL-separated strings blob. Only
+/// pointers at the start of a blob entry (right after a NUL, or at the start
+/// of the data) count, so pointers into the middle of ordinary string data
+/// don't produce fragments.
+pub fn extract_interned_args(
+    args: &[SVal],
+    memory_map: &MemoryMap,
+    config: &ScanConfig,
+    sink: FunctionId,
+    found: &mut CallSites,
+) -> bool {
+    extract_nul_terminated(args, memory_map, config, sink, found, |ptr| {
+        ptr.checked_sub(1)
+            .and_then(|prev| memory_map.get(prev))
+            .is_none_or(|byte| byte == 0)
+    })
+}
+
+fn extract_nul_terminated(
+    args: &[SVal],
+    memory_map: &MemoryMap,
+    config: &ScanConfig,
+    sink: FunctionId,
+    found: &mut CallSites,
+    starts_entry: impl Fn(u32) -> bool,
+) -> bool {
+    let mut read_any = false;
+    for &val in args {
+        if let SVal::Known(ptr) = val {
             let uptr = ptr as u32;
-            let ulen = len as u32;
-            if uptr > 0 && (1..=200).contains(&ulen) {
-                if let Some(s) = read_string(memory_map, uptr, ulen) {
-                    if is_valid_env_name(&s) {
-                        env_vars.insert(s);
-                    }
+            if uptr == 0 || uptr < config.min_ptr || !starts_entry(uptr) {
+                continue;
+            }
+            if let Some(s) = read_cstring(memory_map, uptr, config.max_string_len) {
+                read_any = true;
+                let len = s.len() as u32;
+                record_candidate(s, uptr, len, config, sink, found);
+            }
+        }
+    }
+    read_any
+}
+
+/// Collect env-like words from the messages an `fd_write(fd, iovs,
+/// iovs_len, nwritten)` call writes. Only static iovec arrays can be read:
+/// each 8-byte entry is a `(ptr, len)` pair pointing at part of the message.
+pub fn extract_iovec_words(
+    args: &[SVal],
+    memory_map: &MemoryMap,
+    config: &ScanConfig,
+    sink: FunctionId,
+    found: &mut CallSites,
+) {
+    let [_, SVal::Known(iovs), SVal::Known(iovs_len), _] = *args else {
+        return;
+    };
+    let iovs = iovs as u32;
+    for i in 0..(iovs_len as u32).min(16) {
+        let Some(entry) = iovs.checked_add(i * 8) else {
+            return;
+        };
+        let (Some(ptr), Some(len)) = (
+            read_i32_le(memory_map, entry),
+            entry
+                .checked_add(4)
+                .and_then(|addr| read_i32_le(memory_map, addr)),
+        ) else {
+            continue;
+        };
+        let Some(text) = read_string(memory_map, ptr as u32, len as u32) else {
+            continue;
+        };
+        // Split on single delimiter bytes, so offsets can be tracked by length
+        let mut offset = 0;
+        for word in text
+            .as_bytes()
+            .split(|&b| !(b.is_ascii_alphanumeric() || b == b'_'))
+        {
+            let len = word.len() as u32;
+            if let Ok(word) = std::str::from_utf8(word) {
+                if let Some(rule) = untraced_name_rule(word, config) {
+                    found.logged.push(StringArg {
+                        ptr: ptr as u32 + offset,
+                        len,
+                        value: word.to_string(),
+                        sink,
+                        rule,
+                    });
                 }
             }
+            offset += len + 1;
         }
     }
 }
 
+/// Keep a string read at a call site as a name, or record why it was rejected.
+fn record_candidate(
+    value: String,
+    ptr: u32,
+    len: u32,
+    config: &ScanConfig,
+    sink: FunctionId,
+    found: &mut CallSites,
+) {
+    match check_env_name(&value, config) {
+        Ok(rule) => found.args.push(StringArg {
+            ptr,
+            len,
+            value,
+            sink,
+            rule,
+        }),
+        Err(reason) => found.rejected.push((value, reason)),
+    }
+}
+
 /// Read a string from the memory map at the given pointer and length.
-fn read_string(memory_map: &HashMap<u32, u8>, ptr: u32, len: u32) -> Option<String> {
+fn read_string(memory_map: &MemoryMap, ptr: u32, len: u32) -> Option<String> {
     if len == 0 || len > 1000 {
         return None;
     }
+    let bytes = memory_map.slice(ptr, len)?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
 
-    // Guard against u32 overflow on ptr + len
-    let end = ptr.checked_add(len)?;
+/// Read the initialized bytes of `(ptr, len)` up to the first gap in the
+/// memory map, if there are any.
+fn read_prefix(memory_map: &MemoryMap, ptr: u32, len: u32) -> Option<String> {
+    let bytes = memory_map.prefix(ptr, len)?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
 
-    let mut bytes = Vec::with_capacity(len as usize);
-    for offset in ptr..end {
-        bytes.push(*memory_map.get(&offset)?);
-    }
+/// Read a little-endian `i32` from the memory map.
+pub fn read_i32_le(memory_map: &MemoryMap, addr: u32) -> Option<i32> {
+    let bytes = memory_map.slice(addr, 4)?;
+    Some(i32::from_le_bytes(bytes.try_into().ok()?))
+}
 
-    String::from_utf8(bytes).ok()
+/// Read a NUL-terminated string of at most `max_len` bytes from the memory map.
+fn read_cstring(memory_map: &MemoryMap, ptr: u32, max_len: u32) -> Option<String> {
+    let bytes = memory_map.prefix(ptr, max_len.saturating_add(1))?;
+    let nul = bytes.iter().position(|&byte| byte == 0)?;
+    String::from_utf8(bytes[..nul].to_vec()).ok()
 }
 
 // ===== Validation =====
@@ -124,77 +628,486 @@ const ENV_BLACKLIST: &[&str] = &[
     "General_Category",
 ];
 
+/// Last segments that make a dotted string a file name or host name rather
+/// than a config key.
+const FILE_EXTENSIONS_AND_DOMAINS: &[&str] = &[
+    "com", "org", "net", "io", "dev", "json", "toml", "yaml", "yml", "xml", "ini", "txt", "md",
+    "html", "css", "js", "rs", "py", "sh", "wasm", "wat", "so", "dll", "exe", "png", "svg", "lock",
+    "gz", "zip", "tar",
+];
+
 /// Validate that a string is a syntactically valid environment variable name
-/// and is not in the blacklist of known noise.
-pub fn is_valid_env_name(s: &str) -> bool {
+/// (within the configured length limits) and is not in the blacklist of known
+/// noise or the configured extra noise words.
+pub fn is_valid_env_name(s: &str, config: &ScanConfig) -> bool {
+    check_env_name(s, config).is_ok()
+}
+
+/// Like [`is_valid_env_name`], saying which rule accepted the string or
+/// which check a rejected one failed.
+pub fn check_env_name(s: &str, config: &ScanConfig) -> Result<NameRule, &'static str> {
     let len = s.len();
-    if !(2..=100).contains(&len) {
-        return false;
+    if len < config.min_length.max(1) {
+        return Err("too short");
+    }
+    if len > config.max_length {
+        return Err("too long");
     }
 
     let mut has_letter = false;
-    let mut has_underscore = false;
+    let mut has_separator = false;
+    let mut has_dot_or_dash = false;
 
     for &b in s.as_bytes() {
         match b {
             b'A'..=b'Z' | b'a'..=b'z' => has_letter = true,
             b'0'..=b'9' => {}
-            b'_' => has_underscore = true,
-            _ => return false,
+            b'_' => has_separator = true,
+            b'.' if config.allow_dotted => (has_separator, has_dot_or_dash) = (true, true),
+            b'-' if config.allow_dashed => (has_separator, has_dot_or_dash) = (true, true),
+            _ if config.allow_dotted || config.allow_dashed => {
+                return Err("contains characters other than letters, digits and separators");
+            }
+            _ => return Err("contains characters other than letters, digits and '_'"),
         }
     }
 
     if !has_letter {
-        return false;
+        return Err("has no letters");
+    }
+
+    // Symbols like `_ZN4core3fmt9Formatter3pad17h...E` or `_RNvCs...` from
+    // the name section or debug info
+    #[cfg(feature = "demangle")]
+    if rustc_demangle::try_demangle(s).is_ok() {
+        return Err("is a mangled Rust symbol");
     }
 
     // Must not start or end with underscore (Rust internal symbols)
     if s.as_bytes()[0] == b'_' || s.as_bytes()[len - 1] == b'_' {
-        return false;
+        return Err("starts or ends with '_' (internal symbol)");
     }
 
-    // Must contain an underscore or be all-uppercase 4+ chars
-    if !(has_underscore
-        || len >= 4
+    // `app.database.url`, `FEATURE-FLAG-X`, but no versions, hashes or paths
+    if has_dot_or_dash {
+        if !s.split(['.', '-']).all(is_word_segment) {
+            return Err("has a '.' or '-' separated part that isn't a word");
+        }
+        if s.rsplit_once('.').is_some_and(|(_, last)| {
+            FILE_EXTENSIONS_AND_DOMAINS
+                .iter()
+                .any(|suffix| suffix.eq_ignore_ascii_case(last))
+        }) {
+            return Err("looks like a file name or host name");
+        }
+    }
+
+    // Must contain a separator or be a long enough all-uppercase word
+    if !(has_separator
+        || (len >= config.min_word_length
             && s.bytes()
-                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit()))
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())))
     {
-        return false;
+        return Err("no '_' and not a long enough all-caps word");
     }
 
     // Reject blacklisted noise
-    !ENV_BLACKLIST.contains(&s)
+    if ENV_BLACKLIST.contains(&s)
+        || config
+            .extra_noise
+            .iter()
+            .any(|word| word.eq_ignore_ascii_case(s))
+    {
+        return Err("in the noise list");
+    }
+    if is_ignored(s, config) {
+        return Err("matches an ignore pattern");
+    }
+
+    Ok(if !has_separator {
+        NameRule::AllCapsWord
+    } else if !s.bytes().any(|b| b.is_ascii_lowercase()) {
+        NameRule::UpperSnake
+    } else if s
+        .rsplit(['_', '.', '-'])
+        .next()
+        .is_some_and(|last| is_keyword_segment(last, &config.extra_keywords))
+    {
+        NameRule::Keyword
+    } else {
+        NameRule::Separated
+    })
+}
+
+/// Whether one `.`/`-` separated part of a name is a word: a letter, then
+/// letters and `_`, then optionally a number (`url`, `FLAG`, `v2`). Version
+/// numbers, hex hashes and UUID groups don't fit.
+fn is_word_segment(segment: &str) -> bool {
+    let bytes = segment.as_bytes();
+    let letters = bytes
+        .iter()
+        .position(|b| !(b.is_ascii_alphabetic() || *b == b'_'))
+        .unwrap_or(bytes.len());
+    bytes.first().is_some_and(u8::is_ascii_alphabetic)
+        && bytes[letters..].iter().all(u8::is_ascii_digit)
+}
+
+/// Whether a name matches one of [`ScanConfig::ignore`]'s globs (or, with
+/// the `regex` feature, [`ScanConfig::ignore_regex`]'s regexes).
+fn is_ignored(s: &str, config: &ScanConfig) -> bool {
+    #[cfg(feature = "regex")]
+    if config.ignore_regex.iter().any(|regex| regex.is_match(s)) {
+        return true;
+    }
+    config
+        .ignore
+        .iter()
+        .any(|pattern| glob_matches(pattern.as_bytes(), s.as_bytes()))
+}
+
+/// Match `name` against a glob where `*` is any run of bytes and `?` any one
+/// byte, ignoring ASCII case. Backtracks only to the last `*`, so it's linear
+/// in practice.
+fn glob_matches(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Pattern index after the last `*`, and the name index it's matched up to
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                p += 1;
+                star = Some((p, n));
+            }
+            Some(&b) if b == b'?' || b.eq_ignore_ascii_case(&name[n]) => {
+                p += 1;
+                n += 1;
+            }
+            _ => {
+                // Let the last `*` swallow one more byte, or fail
+                let Some((star_p, star_n)) = star else {
+                    return false;
+                };
+                p = star_p;
+                n = star_n + 1;
+                star = Some((star_p, n));
+            }
+        }
+    }
+    pattern[p..].iter().all(|&b| b == b'*')
+}
+
+/// Whether a name found without a traced `(ptr, len)` read is cased like an
+/// env var: `SCREAMING_SNAKE_CASE`, or, with [`ScanConfig::allow_lowercase`],
+/// any case with a `_` and a keyword as the last segment (`database_url`).
+pub fn has_env_casing(s: &str, config: &ScanConfig) -> bool {
+    is_screaming_snake_case(s)
+        || (config.allow_lowercase
+            && s.rsplit_once('_')
+                .is_some_and(|(_, last)| is_keyword_segment(last, &config.extra_keywords)))
+}
+
+/// The rule accepting a name found without a traced `(ptr, len)` read, if
+/// it's both cased like an env var ([`has_env_casing`]) and a valid name.
+pub fn untraced_name_rule(s: &str, config: &ScanConfig) -> Option<NameRule> {
+    if has_env_casing(s, config) {
+        check_env_name(s, config).ok()
+    } else {
+        None
+    }
+}
+
+/// Whether a string consists only of uppercase ASCII letters, digits and underscores.
+fn is_screaming_snake_case(s: &str) -> bool {
+    s.bytes()
+        .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_')
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_has_env_casing() {
+        let config = ScanConfig::default();
+        assert!(has_env_casing("DATABASE_URL", &config));
+        assert!(!has_env_casing("database_url", &config));
+
+        let config = ScanConfig::new()
+            .allow_lowercase(true)
+            .extra_keywords(["QUEUE"]);
+        assert!(has_env_casing("database_url", &config));
+        assert!(has_env_casing("my_api_token", &config));
+        assert!(has_env_casing("Jobs_Queue", &config));
+        // The keyword has to be the last segment
+        assert!(!has_env_casing("url_parser", &config));
+        assert!(!has_env_casing("cabi_realloc", &config));
+        assert!(!has_env_casing("token", &config));
+    }
+
     #[test]
     fn test_is_valid_env_name() {
+        let config = ScanConfig::default();
         // Valid env var names
-        assert!(is_valid_env_name("DATABASE_URL"));
-        assert!(is_valid_env_name("API_KEY"));
-        assert!(is_valid_env_name("my_var")); // lowercase with underscore
-        assert!(is_valid_env_name("PORT")); // all-caps, 4+ chars
-        assert!(is_valid_env_name("X11_DISPLAY"));
+        assert!(is_valid_env_name("DATABASE_URL", &config));
+        assert!(is_valid_env_name("API_KEY", &config));
+        assert!(is_valid_env_name("my_var", &config)); // lowercase with underscore
+        assert!(is_valid_env_name("PORT", &config)); // all-caps, 4+ chars
+        assert!(is_valid_env_name("X11_DISPLAY", &config));
 
         // Valid with digits (#15)
-        assert!(is_valid_env_name("AWS_S3_BUCKET"));
-        assert!(is_valid_env_name("OAUTH2_CLIENT_ID"));
-        assert!(is_valid_env_name("API_V2_KEY"));
-        assert!(is_valid_env_name("AWS_S3_REGION"));
-        assert!(is_valid_env_name("V2_ENDPOINT"));
+        assert!(is_valid_env_name("AWS_S3_BUCKET", &config));
+        assert!(is_valid_env_name("OAUTH2_CLIENT_ID", &config));
+        assert!(is_valid_env_name("API_V2_KEY", &config));
+
+        // Names mentioning errors are ordinary variables
+        assert!(is_valid_env_name("MY_ERROR_CODE", &config));
+        assert!(is_valid_env_name("Error_Reporting_Url", &config));
+        assert!(is_valid_env_name("AWS_S3_REGION", &config));
+        assert!(is_valid_env_name("V2_ENDPOINT", &config));
 
         // Invalid env var names
-        assert!(!is_valid_env_name("")); // empty
-        assert!(!is_valid_env_name("a")); // too short
-        assert!(!is_valid_env_name("S")); // too short
-        assert!(!is_valid_env_name("HAS SPACE")); // space
-        assert!(!is_valid_env_name("path/to/file")); // slash
-        assert!(!is_valid_env_name("key=value")); // equals
-        assert!(!is_valid_env_name("123")); // no letters
-        assert!(!is_valid_env_name("_PRIVATE")); // starts with underscore
-        assert!(!is_valid_env_name("main")); // no underscore, not all-caps
+        assert!(!is_valid_env_name("", &config)); // empty
+        assert!(!is_valid_env_name("a", &config)); // too short
+        assert!(!is_valid_env_name("S", &config)); // too short
+        assert!(!is_valid_env_name("HAS SPACE", &config)); // space
+        assert!(!is_valid_env_name("path/to/file", &config)); // slash
+        assert!(!is_valid_env_name("key=value", &config)); // equals
+        assert!(!is_valid_env_name("123", &config)); // no letters
+        assert!(!is_valid_env_name("_PRIVATE", &config)); // starts with underscore
+        assert!(!is_valid_env_name("main", &config)); // no underscore, not all-caps
+    }
+
+    #[test]
+    fn test_name_rules() {
+        let config = ScanConfig::default();
+        for (name, rule) in [
+            ("DATABASE_URL", NameRule::UpperSnake),
+            ("X11_DISPLAY", NameRule::UpperSnake),
+            ("PORT", NameRule::AllCapsWord),
+            ("database_url", NameRule::Keyword),
+            ("Api_Key", NameRule::Keyword),
+            ("my_var", NameRule::Separated),
+        ] {
+            assert_eq!(check_env_name(name, &config), Ok(rule), "{name}");
+        }
+
+        // Untraced names need env casing on top of passing a rule
+        assert_eq!(untraced_name_rule("my_var", &config), None);
+        assert_eq!(untraced_name_rule("database_url", &config), None);
+        let config = ScanConfig::new().allow_lowercase(true);
+        assert_eq!(
+            untraced_name_rule("database_url", &config),
+            Some(NameRule::Keyword)
+        );
+        assert_eq!(untraced_name_rule("my_var", &config), None);
+    }
+
+    #[test]
+    fn test_memory_map_merges_writes() {
+        let mut map = MemoryMap::default();
+        map.write(10, b"DATA");
+        map.write(20, b"URL");
+        // Overwrites the overlap and fills the gap, joining both runs
+        map.write(13, b"BASE___");
+        map.write(14, b"\0");
+
+        assert_eq!(map.segments.len(), 1);
+        assert_eq!(read_string(&map, 10, 13).as_deref(), Some("DATB\0SE___URL"));
+        assert_eq!(read_cstring(&map, 10, 32).as_deref(), Some("DATB"));
+        assert_eq!(map.get(9), None);
+        assert_eq!(map.get(23), None);
+
+        // A gap splits runs again
+        map.write(30, b"KEY");
+        assert_eq!(map.segments.len(), 2);
+        assert_eq!(read_string(&map, 20, 13), None);
+        assert_eq!(read_prefix(&map, 20, 13).as_deref(), Some("URL"));
+    }
+
+    #[test]
+    fn test_memory_map_clear() {
+        let mut map = MemoryMap::default();
+        map.write(10, b"DATABASE_URL");
+        map.write(30, b"KEY");
+        map.write(40, b"PORT");
+
+        // Splits the first run, drops the second, trims the third
+        map.clear(14, 28);
+        assert_eq!(map.segments.len(), 2);
+        assert_eq!(read_string(&map, 10, 4).as_deref(), Some("DATA"));
+        assert_eq!(map.get(14), None);
+        assert_eq!(map.get(30), None);
+        assert_eq!(read_string(&map, 42, 2).as_deref(), Some("RT"));
+
+        map.clear(0, 0);
+        map.clear(100, 10);
+        assert_eq!(map.segments.len(), 2);
+    }
+
+    #[test]
+    fn test_memory_map_origins() {
+        let mut map = MemoryMap::default();
+        map.write_data(100, 0, 0, b"HOST_NAME");
+        // A passive segment copied over the middle, from its byte 4 on
+        map.write_data(104, 1, 4, b"PORT");
+        map.write(200, b"NOT_FROM_DATA");
+
+        assert_eq!(map.origin(100), Some((0, 0)));
+        assert_eq!(map.origin(105), Some((1, 5)));
+        assert_eq!(map.origin(108), Some((0, 8)));
+        assert_eq!(map.origin(109), None);
+        assert_eq!(map.origin(200), None);
+    }
+
+    #[test]
+    fn test_reads_near_address_space_end() {
+        let mut map = MemoryMap::default();
+        map.write(0xFFFF_FFFE, b"OK");
+
+        // ptr + len overflows u32: no string, and no panic
+        assert_eq!(read_string(&map, 0xFFFF_FFFE, 10), None);
+        assert_eq!(read_i32_le(&map, 0xFFFF_FFFE), None);
+        assert_eq!(read_cstring(&map, 0xFFFF_FFFE, 10), None);
+        // Ending exactly at the top of the address space is fine
+        assert_eq!(read_string(&map, 0xFFFF_FFFE, 2).as_deref(), Some("OK"));
+    }
+
+    #[test]
+    fn test_configured_thresholds() {
+        let config = ScanConfig::default();
+        assert!(!is_valid_env_name("DB", &config));
+
+        let config = ScanConfig::new().min_word_length(2);
+        assert!(is_valid_env_name("DB", &config));
+        assert!(is_valid_env_name("CI", &config));
+        assert!(!is_valid_env_name("db", &config));
+
+        let config = ScanConfig::new().min_length(5).max_length(10);
+        assert_eq!(check_env_name("A_B", &config), Err("too short"));
+        assert_eq!(check_env_name("DATABASE_URL", &config), Err("too long"));
+        assert_eq!(check_env_name("API_KEY", &config), Ok(NameRule::UpperSnake));
+
+        let config = ScanConfig::new().extra_noise(["build_id"]);
+        assert_eq!(
+            check_env_name("BUILD_ID", &config),
+            Err("in the noise list")
+        );
+        assert_eq!(
+            check_env_name("RUST_BACKTRACE", &config),
+            Err("in the noise list")
+        );
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches(b"INTERNAL_*", b"INTERNAL_BUILD_ID"));
+        assert!(glob_matches(b"*_sentinel", b"MAX_SENTINEL"));
+        assert!(glob_matches(b"A?I_*KEY*", b"API_SIGNING_KEY_V2"));
+        assert!(glob_matches(b"*", b""));
+        assert!(glob_matches(b"**X", b"XX"));
+        assert!(!glob_matches(b"INTERNAL_*", b"MY_INTERNAL_ID"));
+        assert!(!glob_matches(b"?", b""));
+        assert!(!glob_matches(b"A*B", b"ABA"));
+
+        let config = ScanConfig::new().ignore(["TEST_*"]);
+        assert_eq!(
+            check_env_name("TEST_FIXTURE_PATH", &config),
+            Err("matches an ignore pattern")
+        );
+        assert_eq!(
+            check_env_name("DATABASE_URL", &config),
+            Ok(NameRule::UpperSnake)
+        );
+    }
+
+    #[test]
+    fn test_check_env_name_reasons() {
+        let config = ScanConfig::default();
+        assert_eq!(
+            check_env_name("DATABASE_URL", &config),
+            Ok(NameRule::UpperSnake)
+        );
+        assert_eq!(check_env_name("a", &config), Err("too short"));
+        assert_eq!(
+            check_env_name("RUST_BACKTRACE", &config),
+            Err("in the noise list")
+        );
+        assert_eq!(
+            check_env_name("_ZN4core3fmt", &config),
+            Err("starts or ends with '_' (internal symbol)")
+        );
+    }
+
+    #[test]
+    fn test_dotted_and_dashed_names() {
+        let config = ScanConfig::default();
+        assert!(check_env_name("app.database.url", &config).is_err());
+        assert!(check_env_name("FEATURE-FLAG-X", &config).is_err());
+
+        let config = ScanConfig::new().allow_dotted(true).allow_dashed(true);
+        for (name, rule) in [
+            ("app.database.url", NameRule::Keyword),
+            ("FEATURE-FLAG-X", NameRule::UpperSnake),
+            ("server.port", NameRule::Keyword),
+            ("cache.ttl_seconds", NameRule::Separated),
+            ("api-v2.key", NameRule::Keyword),
+        ] {
+            assert_eq!(check_env_name(name, &config), Ok(rule), "{name}");
+        }
+        for (name, reason) in [
+            ("config.json", "looks like a file name or host name"),
+            ("www.example.com", "looks like a file name or host name"),
+            (
+                "libc.so.6",
+                "has a '.' or '-' separated part that isn't a word",
+            ),
+            (
+                "app..url",
+                "has a '.' or '-' separated part that isn't a word",
+            ),
+            ("FLAG-", "has a '.' or '-' separated part that isn't a word"),
+            (
+                "d3b07384-d9a0-4c9b-8f6e-1f2a3b4c5d6e",
+                "has a '.' or '-' separated part that isn't a word",
+            ),
+            (
+                "app/database.url",
+                "contains characters other than letters, digits and separators",
+            ),
+        ] {
+            assert_eq!(check_env_name(name, &config), Err(reason), "{name}");
+        }
+
+        // Each separator needs its own opt-in
+        let config = ScanConfig::new().allow_dotted(true);
+        assert!(check_env_name("app.database.url", &config).is_ok());
+        assert!(check_env_name("FEATURE-FLAG-X", &config).is_err());
+    }
+
+    #[cfg(feature = "demangle")]
+    #[test]
+    fn test_rejects_mangled_symbols() {
+        let config = ScanConfig::default();
+        for symbol in [
+            "_ZN4core3fmt9Formatter3pad17h0123456789abcdefE",
+            // Without the leading `_`, as some toolchains emit it
+            "ZN3FOO3BARE",
+            // v0 mangling
+            "_RNvCs1234_7mycrate4main",
+        ] {
+            assert_eq!(
+                check_env_name(symbol, &config),
+                Err("is a mangled Rust symbol"),
+                "{symbol}"
+            );
+        }
+        assert_eq!(
+            check_env_name("ZONE_NAME", &config),
+            Ok(NameRule::UpperSnake)
+        );
+        assert_eq!(
+            check_env_name("RUST_LOG", &config),
+            Ok(NameRule::UpperSnake)
+        );
     }
 }
@@ -0,0 +1,192 @@
+//! Stubbing of function bodies walrus can't load.
+//!
+//! walrus validates with a fixed set of wasm features and rejects the whole
+//! module if a single function uses anything newer (relaxed SIMD, typed
+//! function references, ...). When that happens we validate each body
+//! ourselves, replace the ones that fail with a bare `unreachable`, and retry.
+//! Env var names passed from the stubbed functions are lost, but the rest of
+//! the module still scans.
+//!
+//! Before stubbing, locals declared with a reference type walrus can't load
+//! (GC and typed function references) are retyped to `i32`. The analysis never
+//! looks at local types, so a function that only *declares* such a local is
+//! then scanned in full; one that actually uses it still fails validation and
+//! is stubbed.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use wasmparser::{
+    BinaryReader, FuncValidatorAllocations, Parser, RefType, ValType, ValidPayload, Validator,
+    WasmFeatures,
+};
+
+use crate::const_fold::write_u32_leb;
+
+const SECTION_CODE: u8 = 10;
+
+/// A body with no locals that traps: `unreachable end`.
+const STUB_BODY: [u8; 3] = [0x00, 0x00, 0x0b];
+
+const VAL_TYPE_I32: u8 = 0x7f;
+
+/// A module with its unsupported function bodies stubbed out.
+pub struct StubbedModule {
+    pub bytes: Vec<u8>,
+    /// `(function index, validation error)` for each stubbed function.
+    pub skipped: Vec<(u32, String)>,
+}
+
+/// Stub out every function body that fails validation under the features
+/// walrus supports. Returns `None` if nothing could be stubbed, e.g. because
+/// the module fails outside the code section.
+pub fn stub_unsupported_functions(module_bytes: &[u8]) -> Option<StubbedModule> {
+    // Body start offset → (function index, error message)
+    let mut failing: HashMap<usize, (u32, String)> = HashMap::new();
+    let mut validator = Validator::new_with_features(walrus_features());
+
+    for payload in Parser::new(0).parse_all(module_bytes) {
+        match validator.payload(&payload.ok()?).ok()? {
+            ValidPayload::Func(func, body) => {
+                let mut func = func.into_validator(FuncValidatorAllocations::default());
+                if let Err(e) = func.validate(&body) {
+                    failing.insert(body.range().start, (func.index(), e.message().to_string()));
+                }
+            }
+            ValidPayload::Ok | ValidPayload::End(_) => {}
+            ValidPayload::Parser(_) => return None,
+        }
+    }
+    if failing.is_empty() {
+        return None;
+    }
+
+    let bytes = rewrite_code_section(module_bytes, |body_start, body| {
+        if failing.contains_key(&body_start) {
+            Cow::Borrowed(&STUB_BODY[..])
+        } else {
+            Cow::Borrowed(body)
+        }
+    })?;
+    let mut skipped: Vec<(u32, String)> = failing.into_values().collect();
+    skipped.sort();
+    Some(StubbedModule { bytes, skipped })
+}
+
+/// Retype locals declared with a reference type walrus doesn't support to
+/// `i32`. Returns the input unchanged (borrowed) when there are none or the
+/// module can't be walked.
+pub fn retype_unsupported_locals(module_bytes: &[u8]) -> Cow<'_, [u8]> {
+    let mut changed = false;
+    let rewritten = rewrite_code_section(module_bytes, |body_start, body| {
+        match retype_locals(body, body_start) {
+            Some(body) => {
+                changed = true;
+                Cow::Owned(body)
+            }
+            None => Cow::Borrowed(body),
+        }
+    });
+    match rewritten {
+        Some(rewritten) if changed => Cow::Owned(rewritten),
+        _ => Cow::Borrowed(module_bytes),
+    }
+}
+
+/// A copy of `body` with unsupported local types replaced, or `None` if it
+/// has none. A local type that fails to parse leaves the body as it is.
+fn retype_locals(body: &[u8], body_start: usize) -> Option<Vec<u8>> {
+    let mut reader = BinaryReader::new_with_offset(body, body_start);
+    let mut out = Vec::with_capacity(body.len());
+    let mut changed = false;
+
+    let groups = reader.read_var_u32().ok()?;
+    write_u32_leb(&mut out, groups);
+    for _ in 0..groups {
+        let count = reader.read_var_u32().ok()?;
+        write_u32_leb(&mut out, count);
+        let ty_start = reader.current_position();
+        let ty = reader.read::<ValType>().ok()?;
+        match ty {
+            ValType::Ref(ty) if ty != RefType::FUNCREF && ty != RefType::EXTERNREF => {
+                out.push(VAL_TYPE_I32);
+                changed = true;
+            }
+            _ => out.extend_from_slice(&body[ty_start..reader.current_position()]),
+        }
+    }
+    if !changed {
+        return None;
+    }
+    out.extend_from_slice(&body[reader.current_position()..]);
+    Some(out)
+}
+
+/// Rebuild the code section, passing every body (with the offset it starts
+/// at) through `map_body`.
+fn rewrite_code_section<'a>(
+    module_bytes: &'a [u8],
+    mut map_body: impl FnMut(usize, &'a [u8]) -> Cow<'a, [u8]>,
+) -> Option<Vec<u8>> {
+    // Same section walk as `const_fold`: magic + version, then (id, size, contents)
+    let mut reader = BinaryReader::new_with_offset(module_bytes.get(8..)?, 8);
+    let mut out = module_bytes[..8].to_vec();
+
+    while !reader.eof() {
+        let section_start = reader.original_position();
+        let id = reader.read_u8().ok()?;
+        let size = reader.read_var_u32().ok()? as usize;
+        let contents_start = reader.original_position();
+        let contents = reader.read_bytes(size).ok()?;
+
+        if id != SECTION_CODE {
+            out.extend_from_slice(&module_bytes[section_start..contents_start + size]);
+            continue;
+        }
+
+        let mut entries = BinaryReader::new_with_offset(contents, contents_start);
+        let count = entries.read_var_u32().ok()?;
+        let mut new_contents = Vec::new();
+        write_u32_leb(&mut new_contents, count);
+        for _ in 0..count {
+            let body_size = entries.read_var_u32().ok()? as usize;
+            let body_start = entries.original_position();
+            let body = map_body(body_start, entries.read_bytes(body_size).ok()?);
+            write_u32_leb(&mut new_contents, body.len() as u32);
+            new_contents.extend_from_slice(&body);
+        }
+
+        out.push(id);
+        write_u32_leb(&mut out, new_contents.len() as u32);
+        out.extend_from_slice(&new_contents);
+    }
+
+    Some(out)
+}
+
+/// The feature set walrus 0.22 validates with.
+fn walrus_features() -> WasmFeatures {
+    WasmFeatures {
+        mutable_global: true,
+        saturating_float_to_int: true,
+        sign_extension: true,
+        reference_types: true,
+        multi_value: true,
+        bulk_memory: true,
+        simd: true,
+        relaxed_simd: false,
+        threads: true,
+        tail_call: true,
+        floats: true,
+        multi_memory: true,
+        exceptions: false,
+        memory64: true,
+        extended_const: false,
+        component_model: false,
+        function_references: false,
+        memory_control: false,
+        gc: false,
+        component_model_values: false,
+        component_model_nested_names: false,
+    }
+}
@@ -0,0 +1,227 @@
+//! Interactive browser for scan results (`--interactive`, requires the `tui`
+//! feature).
+//!
+//! Variables are listed on the left; the right pane shows every hit for the
+//! selected one. `/` filters the list by substring.
+
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use wasm2env::{Category, ScanReport};
+
+/// Run the browser until the user quits.
+pub fn run(path: &str, report: &ScanReport) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = App::new(path, report).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+struct App<'a> {
+    path: &'a str,
+    report: &'a ScanReport,
+    names: Vec<String>,
+    filter: String,
+    editing_filter: bool,
+    list: ListState,
+}
+
+impl<'a> App<'a> {
+    fn new(path: &'a str, report: &'a ScanReport) -> Self {
+        let names = report.names();
+        let list = ListState::default().with_selected((!names.is_empty()).then_some(0));
+        Self {
+            path,
+            report,
+            names,
+            filter: String::new(),
+            editing_filter: false,
+            list,
+        }
+    }
+
+    /// Names matching the filter (case-insensitive substring).
+    fn visible(&self) -> Vec<&str> {
+        let filter = self.filter.to_lowercase();
+        self.names
+            .iter()
+            .filter(|name| name.to_lowercase().contains(&filter))
+            .map(String::as_str)
+            .collect()
+    }
+
+    fn selected(&self) -> Option<&str> {
+        self.visible().get(self.list.selected()?).copied()
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Returns `false` when the user quits.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.editing_filter {
+            match key.code {
+                KeyCode::Char(c) => self.filter.push(c),
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.editing_filter = false;
+                }
+                KeyCode::Enter => self.editing_filter = false,
+                _ => {}
+            }
+            let has_matches = !self.visible().is_empty();
+            self.list.select(has_matches.then_some(0));
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('/') => self.editing_filter = true,
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            _ => {}
+        }
+        true
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let len = self.visible().len();
+        if len == 0 {
+            return;
+        }
+        let current = self.list.selected().unwrap_or(0);
+        let next = current.saturating_add_signed(delta).min(len - 1);
+        self.list.select(Some(next));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, filter_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(3)]).areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(main);
+
+        let visible = self.visible();
+        let title = format!(" {} ({}/{}) ", self.path, visible.len(), self.names.len());
+        let list = List::new(visible.iter().map(|name| (*name).to_string()))
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let details =
+            Paragraph::new(self.detail_lines()).block(Block::bordered().title(" Details "));
+        frame.render_widget(details, detail_area);
+
+        let filter_title = if self.editing_filter {
+            " Filter (Enter to apply, Esc to clear) "
+        } else {
+            " / filter   j/k move   q quit "
+        };
+        let filter =
+            Paragraph::new(self.filter.as_str()).block(Block::bordered().title(filter_title));
+        frame.render_widget(filter, filter_area);
+    }
+
+    fn detail_lines(&self) -> Vec<Line<'static>> {
+        let Some(name) = self.selected() else {
+            return vec![Line::from("No variable selected.")];
+        };
+
+        let mut lines = vec![
+            Line::from(name.to_string()).style(Style::new().add_modifier(Modifier::BOLD)),
+            Line::from(format!("category: {:?}", Category::of(name))),
+        ];
+        for hit in self.report.hits.iter().filter(|hit| hit.name == name) {
+            lines.push(Line::from(""));
+            let function = match (hit.function_index, &hit.function_name) {
+                (Some(index), Some(name)) => format!("{index} `{name}`"),
+                (Some(index), None) => index.to_string(),
+                (None, _) => "-".to_string(),
+            };
+            lines.push(Line::from(format!(
+                "module {} / function {function} ({:?})",
+                hit.module_index, hit.source
+            )));
+            lines.push(Line::from(format!(
+                "  confidence: {:?} (score {}), name rule {:?}",
+                hit.confidence, hit.score, hit.rule
+            )));
+            if let Some(ptr) = hit.ptr {
+                lines.push(Line::from(format!("  offset: {ptr:#x}, len {}", hit.len)));
+            }
+            if let Some(file_offset) = hit.file_offset {
+                lines.push(Line::from(format!("  file offset: {file_offset:#x}")));
+            }
+            if let Some(sink) = &hit.sink {
+                lines.push(Line::from(format!("  passed to: {sink}")));
+            }
+            if let Some(default) = &hit.default {
+                lines.push(Line::from(format!("  default: {default:?}")));
+            }
+            if hit.suspicious_overlap {
+                lines.push(Line::from(
+                    "  suspicious: same offset read with another length",
+                ));
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::KeyModifiers;
+    use wasm2env::{Confidence, EnvVarHit, HitSource, NameRule};
+
+    fn hit(name: &str) -> EnvVarHit {
+        EnvVarHit {
+            name: name.to_string(),
+            ptr: Some(0x400),
+            file_offset: None,
+            len: name.len() as u32,
+            module_index: 0,
+            function_index: Some(1),
+            function_name: None,
+            source: HitSource::CallSite,
+            rule: NameRule::UpperSnake,
+            confidence: Confidence::High,
+            score: 90,
+            sink: None,
+            default: None,
+            suspicious_overlap: false,
+            corroborated: false,
+        }
+    }
+
+    #[test]
+    fn test_filter() {
+        let report = ScanReport {
+            hits: vec![hit("API_KEY"), hit("DATABASE_URL"), hit("REDIS_URL")],
+            ..ScanReport::default()
+        };
+        let mut app = App::new("app.wasm", &report);
+        for code in [KeyCode::Char('/'), KeyCode::Char('u'), KeyCode::Char('r')] {
+            app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+
+        assert_eq!(app.visible(), vec!["DATABASE_URL", "REDIS_URL"]);
+        assert_eq!(app.selected(), Some("DATABASE_URL"));
+    }
+}
@@ -0,0 +1,51 @@
+//! `--watch`: rescan a module whenever it changes on disk.
+
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use notify::{RecursiveMode, Watcher};
+
+/// How long the file has to stay quiet before we rescan. Linkers and
+/// `wasm-tools` write in several steps, so one rebuild fires a burst of events.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Call `rescan` once, then again after every burst of changes to `path`.
+/// Runs until the watcher fails (or the process is interrupted).
+pub fn watch(path: &str, mut rescan: impl FnMut()) -> Result<()> {
+    let path = Path::new(path);
+    let file_name = path.file_name().context("--watch expects a file path")?;
+    // Watch the directory rather than the file: build tools usually replace
+    // the output with a rename, which would orphan a watch on the old inode
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .with_context(|| format!("Failed to watch {}", dir.display()))?;
+
+    rescan();
+    loop {
+        let event = rx.recv().context("File watcher stopped")??;
+        if !touches(&event, file_name) || event.kind.is_access() {
+            continue;
+        }
+        // Drain the rest of the burst
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            event?;
+        }
+        rescan();
+    }
+}
+
+fn touches(event: &notify::Event, file_name: &std::ffi::OsStr) -> bool {
+    event
+        .paths
+        .iter()
+        .any(|changed| changed.file_name() == Some(file_name))
+}
@@ -0,0 +1,8 @@
+;; A component importing `wasi:cli/environment` whose core module never
+;; calls it: the import is only visible at the component level.
+(component
+  (import "wasi:cli/environment@0.2.0" (instance
+    (export "get-environment" (func (result (list (tuple string string)))))))
+  (import "wasi:cli/stdout@0.2.0" (instance))
+  (core module $m
+    (func (export "run"))))
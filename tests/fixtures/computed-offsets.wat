@@ -0,0 +1,30 @@
+;; Names in a fixed-stride string table, addressed through computed offsets
+;; rather than plain constants.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL....")
+  (data (i32.const 1040) "REDIS_HOST......")
+  (data (i32.const 1056) "LOG_LEVEL.......")
+  (data (i32.const 1072) "API_TOKEN.......")
+  (data (i32.const 1088) "CACHE_DIR.......")
+
+  (func (export "run") (param $i i32)
+    ;; base + index * stride
+    (local.set $i (i32.const 1))
+    (call $get (i32.add (i32.const 1024) (i32.mul (local.get $i) (i32.const 16)))
+               (i32.const 10) (i32.const 4096))
+    ;; base + (index << 4)
+    (call $get (i32.add (i32.const 1024) (i32.shl (i32.const 2) (i32.const 4)))
+               (i32.const 9) (i32.const 4096))
+    ;; a tagged pointer with its low bits masked off, and a length unpacked
+    ;; from the high half of a word
+    (call $get (i32.and (i32.const 1075) (i32.const -16))
+               (i32.shr_u (i32.const 0x00090000) (i32.const 16)) (i32.const 4096))
+    ;; or / xor building the address
+    (call $get (i32.or (i32.const 1024) (i32.const 64))
+               (i32.xor (i32.const 9) (i32.const 0)) (i32.const 4096))
+    ;; end - size
+    (call $get (i32.sub (i32.const 1036) (i32.const 12))
+               (i32.shr_s (i32.const 48) (i32.const 2)) (i32.const 4096))))
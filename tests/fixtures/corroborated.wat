@@ -0,0 +1,18 @@
+;; DATABASE_URL is both read through a config import and named in an error
+;; message; LOG_LEVEL only appears in the message.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (import "wasi_snapshot_preview1" "fd_write"
+    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "DATABASE_URL and LOG_LEVEL must be set\n")
+  ;; iovec array: { buf = 1040, buf_len = 39 }
+  (data (i32.const 2048) "\10\04\00\00\27\00\00\00")
+
+  (func (export "read_config")
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096)))
+
+  (func (export "fail")
+    (drop (call $fd_write (i32.const 2) (i32.const 2048) (i32.const 1) (i32.const 4096)))))
@@ -0,0 +1,13 @@
+;; A host ABI the built-in import list doesn't know: one lookup takes
+;; (ptr, len), another a NUL-terminated name.
+(module
+  (import "my-host" "config-get" (func $config_get (param i32 i32) (result i32)))
+  (import "my-host" "getenv" (func $getenv (param i32) (result i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "LOG_LEVEL\00")
+
+  (func (export "run")
+    (drop (call $config_get (i32.const 1024) (i32.const 12)))
+    (drop (call $getenv (i32.const 1040)))))
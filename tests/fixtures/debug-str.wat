@@ -0,0 +1,6 @@
+;; An unstripped build: the variable's name survives only in the DWARF string
+;; table, next to ordinary identifiers.
+(module
+  (memory 1)
+  (func (export "run"))
+  (@custom ".debug_str" "clang version 17\00DATABASE_URL\00main\00__stack_pointer\00"))
@@ -0,0 +1,16 @@
+;; Config keys in dotted and dashed styles, next to a file name that must
+;; not pass for one.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "app.database.url")
+  (data (i32.const 1056) "FEATURE-FLAG-X")
+  (data (i32.const 1088) "settings.json")
+  (data (i32.const 1120) "LOG_LEVEL")
+
+  (func (export "read_config")
+    (call $get (i32.const 1024) (i32.const 16) (i32.const 4096))
+    (call $get (i32.const 1056) (i32.const 14) (i32.const 4096))
+    (call $get (i32.const 1088) (i32.const 13) (i32.const 4096))
+    (call $get (i32.const 1120) (i32.const 9) (i32.const 4096))))
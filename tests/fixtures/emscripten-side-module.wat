@@ -0,0 +1,19 @@
+;; Emscripten-style side module: carries a `dylink.0` section and resolves
+;; libc's `getenv` from the main module, passing NUL-terminated names.
+(module
+  (@custom "dylink.0" (before first) "\01\04\20\00\00\00")
+  (import "env" "memory" (memory 1))
+  (import "env" "getenv" (func $getenv (param i32) (result i32)))
+
+  (data (i32.const 1024) "DATABASE_URL\00")
+  (data (i32.const 1040) "LOG_FORMAT\00")
+
+  (func $read_config (export "read_config") (result i32)
+    (drop (call $getenv (i32.const 1024)))
+    (call $getenv (i32.const 1040)))
+
+  ;; Looks like an env var, but never reaches getenv
+  (data (i32.const 1056) "NOT_AN_ENV_VAR\00")
+  (func $log (param i32))
+  (func (export "noise")
+    (call $log (i32.const 1056))))
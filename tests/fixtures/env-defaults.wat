@@ -0,0 +1,44 @@
+;; Env reads with a fallback value sitting next to the call.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (import "env" "getenv" (func $getenv (param i32) (result i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "PORT")
+  (data (i32.const 1028) "8080")
+  (data (i32.const 1040) "LOG_LEVEL\00")
+  (data (i32.const 1056) "info\00")
+  (data (i32.const 1072) "API_KEY")
+  (data (i32.const 1088) "API_KEY must be set")
+  (data (i32.const 1120) "HOST")
+  (data (i32.const 1136) "localhost")
+
+  (func $memcpy (param i32 i32 i32) (result i32) (local.get 0))
+  (func $alloc (param i32 i32) (result i32) (i32.const 0))
+  (func $expect_failed (param i32 i32))
+
+  ;; `env::var("PORT").unwrap_or("8080".into())`: the literal is copied into
+  ;; a fresh allocation after the lookup
+  (func (export "port") (result i32)
+    (call $get (i32.const 1024) (i32.const 4) (i32.const 4096))
+    (drop (call $alloc (i32.const 4) (i32.const 1)))
+    (call $memcpy (i32.const 8192) (i32.const 1028) (i32.const 4)))
+
+  ;; `getenv("LOG_LEVEL") ?: "info"`
+  (func (export "log_level") (result i32)
+    (local $v i32)
+    (local.set $v (call $getenv (i32.const 1040)))
+    (select (local.get $v) (i32.const 1056) (local.get $v)))
+
+  ;; `env::var("API_KEY").expect(..)`: a message, not a default
+  (func (export "api_key")
+    (call $get (i32.const 1072) (i32.const 7) (i32.const 4096))
+    (call $expect_failed (i32.const 1088) (i32.const 19)))
+
+  ;; Too many calls later to be the fallback
+  (func (export "host") (result i32)
+    (call $get (i32.const 1120) (i32.const 4) (i32.const 4096))
+    (drop (call $alloc (i32.const 1) (i32.const 1)))
+    (drop (call $alloc (i32.const 2) (i32.const 1)))
+    (drop (call $alloc (i32.const 3) (i32.const 1)))
+    (call $memcpy (i32.const 8192) (i32.const 1136) (i32.const 9))))
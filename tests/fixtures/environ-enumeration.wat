@@ -0,0 +1,12 @@
+;; Copies out the whole environment with environ_get and never names a
+;; variable.
+(module
+  (import "wasi_snapshot_preview1" "environ_sizes_get"
+    (func $environ_sizes_get (param i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "environ_get"
+    (func $environ_get (param i32 i32) (result i32)))
+  (memory 1)
+
+  (func (export "run")
+    (drop (call $environ_sizes_get (i32.const 0) (i32.const 4)))
+    (drop (call $environ_get (i32.const 8) (i32.const 1024)))))
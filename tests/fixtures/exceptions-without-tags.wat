@@ -0,0 +1,22 @@
+;; Exception handling with no tag anywhere: catch_all, delegate and rethrow
+;; don't need one, so this must be lowered like a module with tags.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "LOG_FORMAT")
+
+  (func (export "run")
+    try
+      (call $get (i32.const 1024) (i32.const 12) (i32.const 4096))
+    catch_all
+      rethrow 0
+    end)
+
+  (func (export "delegated")
+    block $outer
+      try
+        (call $get (i32.const 1040) (i32.const 10) (i32.const 4096))
+      delegate $outer
+    end))
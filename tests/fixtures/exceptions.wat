@@ -0,0 +1,41 @@
+;; Legacy exception handling around config reads: the protected bodies must
+;; still be analyzed, and the module must load despite its tag section.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (import "env" "host_error" (tag $host_error (param i32)))
+  (tag $error (param i32))
+  (export "error" (tag $error))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "LOG_FORMAT")
+  (data (i32.const 1056) "REDIS_HOST")
+
+  (func (export "run") (result i32)
+    try (result i32)
+      (call $get (i32.const 1024) (i32.const 12) (i32.const 4096))
+      i32.const 0
+    catch $error
+      ;; caught payload is on the stack
+      drop
+      try
+        (throw $host_error (i32.const 1))
+      catch_all
+        rethrow 0
+      end
+      i32.const 1
+    catch_all
+      i32.const 2
+    end)
+
+  (func (export "delegated")
+    block $outer
+      try
+        try
+          (call $get (i32.const 1040) (i32.const 10) (i32.const 4096))
+        delegate $outer
+        (call $get (i32.const 1056) (i32.const 10) (i32.const 4096))
+      catch $host_error
+        drop
+      end
+    end))
@@ -0,0 +1,7 @@
+;; One function exported under two spellings of the same key, around a third
+;; name that sorts between them.
+(module
+  (func $config
+    (export "DB_URL")
+    (export "EXTRA_PATH")
+    (export "db_url")))
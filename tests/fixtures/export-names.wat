@@ -0,0 +1,6 @@
+;; Config keys surfaced only as export names, next to ordinary exports.
+(module
+  (memory (export "memory") 1)
+  (global (export "FEATURE_FLAGS") i32 (i32.const 0))
+  (func (export "LEGACY_CONFIG_PATH"))
+  (func (export "cabi_realloc")))
@@ -0,0 +1,20 @@
+;; Module using extended-const expressions (as emitted by newer LLVM) for data
+;; segment offsets and global initializers.
+(module
+  (import "env" "memory" (memory 1))
+  (import "env" "__memory_base" (global $memory_base i32))
+  (import "env" "getenv" (func $getenv (param i32) (result i32)))
+
+  (global $strings i32 (i32.add (i32.const 1000) (i32.const 24)))
+  (global $second i32 (i32.add (global.get $strings) (i32.const 16)))
+
+  (data (i32.add (i32.const 1000) (i32.const 24)) "DATABASE_URL\00")
+  (data (i32.sub (i32.mul (i32.const 2) (i32.const 540)) (i32.const 40)) "LOG_FORMAT\00")
+
+  ;; Depends on an import, so it can't be placed statically
+  (data (i32.add (global.get $memory_base) (i32.const 2048)) "UNPLACED_VAR\00")
+
+  (func (export "read_config") (result i32)
+    (drop (call $getenv (global.get $strings)))
+    (drop (call $getenv (global.get $memory_base)))
+    (call $getenv (global.get $second))))
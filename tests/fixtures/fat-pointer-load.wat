@@ -0,0 +1,18 @@
+;; A `(ptr, len)` fat pointer stored in a static: the name is passed by
+;; loading both fields from the same base with different memarg offsets.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  ;; struct { ptr: 1024, len: 12 } at 2048, little-endian
+  (data (i32.const 2048) "\00\04\00\00\0c\00\00\00")
+
+  (global $statics i32 (i32.const 2040))
+
+  (func (export "read_config") (local $base i32)
+    (local.set $base (i32.add (global.get $statics) (i32.const 8)))
+    (call $get
+      (i32.load offset=0 (local.get $base))
+      (i32.load offset=4 (local.get $base))
+      (i32.const 4096))))
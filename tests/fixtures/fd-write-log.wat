@@ -0,0 +1,13 @@
+;; A module that never reads the environment through a traceable call, but
+;; writes an error naming the variable to stderr with fd_write.
+(module
+  (import "wasi_snapshot_preview1" "fd_write"
+    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "error: DATABASE_URL is not set\n")
+  ;; iovec array: { buf = 1024, buf_len = 31 }
+  (data (i32.const 2048) "\00\04\00\00\1f\00\00\00")
+
+  (func (export "run")
+    (drop (call $fd_write (i32.const 2) (i32.const 2048) (i32.const 1) (i32.const 4096)))))
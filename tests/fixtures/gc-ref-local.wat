@@ -0,0 +1,11 @@
+;; A function declaring locals of GC reference types, which walrus can't load.
+;; The locals are never used, so the function must still be scanned.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+
+  (func (export "read_config")
+    (local anyref (ref null i31) i32)
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096))))
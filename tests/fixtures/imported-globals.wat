@@ -0,0 +1,22 @@
+;; An imported global shifts the index of every defined one. The pointer
+;; comes from the defined global (index 1), so reading index 0's slot would
+;; lose it; the imported global's own value isn't known statically.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (import "env" "stack_top" (global $stack_top i32))
+  (global $name_ptr i32 (i32.const 1024))
+  (memory 1)
+
+  (data (i32.const 1024) "QUEUE_URL")
+
+  (func (export "run")
+    global.get $name_ptr
+    i32.const 9
+    i32.const 4096
+    call $get)
+
+  (func (export "unknown")
+    global.get $stack_top
+    i32.const 9
+    i32.const 4096
+    call $get))
@@ -0,0 +1,11 @@
+;; Names interned into one NUL-separated blob and passed to a (ptr, len)
+;; import with a length only known at runtime.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL\00LOG_LEVEL\00")
+
+  (func (export "run") (param $len i32)
+    (call $get (i32.const 1024) (local.get $len) (i32.const 4096))
+    (call $get (i32.const 1037) (local.get $len) (i32.const 4096))))
@@ -0,0 +1,21 @@
+;; The usual rustc shape for `&str` statics: the length is loaded from the
+;; slice's len field into a local, and `local.get` at the call site.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "LOG_LEVEL")
+  ;; [&str; 2] at 2048: { 1024, 12 }, { 1040, 9 }
+  (data (i32.const 2048) "\00\04\00\00\0c\00\00\00\10\04\00\00\09\00\00\00")
+
+  (func (export "database") (local $len i32)
+    (local.set $len (i32.load offset=4 (i32.const 2048)))
+    (call $get (i32.const 1024) (local.get $len) (i32.const 4096)))
+
+  ;; Both fields loaded into locals, used inside a nested block
+  (func (export "log") (local $ptr i32) (local $len i32)
+    (local.set $ptr (i32.load offset=8 (i32.const 2048)))
+    (drop (local.tee $len (i32.load offset=12 (i32.const 2048))))
+    (block
+      (call $get (local.get $ptr) (local.get $len) (i32.const 4096)))))
@@ -0,0 +1,9 @@
+;; Lowercase config keys surfaced as export names, as some Node/Deno-targeted
+;; builds use them, next to ordinary snake_case exports.
+(module
+  (memory (export "memory") 1)
+  (global (export "database_url") i32 (i32.const 0))
+  (func (export "my_api_token"))
+  (func (export "Redis_Host"))
+  (func (export "cabi_realloc"))
+  (func (export "run_main")))
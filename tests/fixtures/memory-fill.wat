@@ -0,0 +1,15 @@
+;; A startup function zeroes the region holding one name before anything
+;; reads it, so the bytes from the data segment are never seen.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "LOG_LEVEL")
+
+  (func $init (export "_initialize")
+    (memory.fill (i32.const 1024) (i32.const 0) (i32.const 12)))
+
+  (func (export "read_config")
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096))
+    (call $get (i32.const 1040) (i32.const 9) (i32.const 4096))))
@@ -0,0 +1,18 @@
+;; A memory64 module: pointers and lengths are i64, including the fields of
+;; a fat pointer stored in a static and a pointer built from a base + offset.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i64 i64 i64)))
+  (memory i64 1)
+
+  (global $base i64 (i64.const 1024))
+
+  (data (i64.const 512) "\00\04\00\00\00\00\00\00\0c\00\00\00\00\00\00\00")
+  (data (i64.const 1024) "DATABASE_URL")
+  (data (i64.const 1040) "REDIS_HOST")
+  (data (i64.const 1056) "LOG_LEVEL")
+
+  (func (export "run") (param $len i32)
+    (call $get (i64.load (i64.const 512)) (i64.load offset=8 (i64.const 512)) (i64.const 4096))
+    (call $get (i64.add (global.get $base) (i64.const 16)) (i64.const 10) (i64.const 4096))
+    (local.set $len (i32.const 9))
+    (call $get (i64.const 1056) (i64.extend_i32_u (local.get $len)) (i64.const 4096))))
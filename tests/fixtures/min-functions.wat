@@ -0,0 +1,15 @@
+;; DATABASE_URL is read from two functions, LOG_LEVEL twice from one.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "LOG_LEVEL")
+
+  (func (export "connect")
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096)))
+
+  (func (export "migrate")
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096))
+    (call $get (i32.const 1040) (i32.const 9) (i32.const 4096))
+    (call $get (i32.const 1040) (i32.const 9) (i32.const 4096))))
@@ -0,0 +1,15 @@
+;; The same variable looked up with two spellings, as from two codegen paths.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "Database_Url")
+  (data (i32.const 1056) "LOG_LEVEL")
+
+  (func (export "connect")
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096)))
+  (func (export "reconnect")
+    (call $get (i32.const 1040) (i32.const 12) (i32.const 4096)))
+  (func (export "log")
+    (call $get (i32.const 1056) (i32.const 9) (i32.const 4096))))
@@ -0,0 +1,48 @@
+;; Blocks with params and multiple results, and calls that leave the operand
+;; stack below their arguments intact.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "LOG_FORMAT")
+  (data (i32.const 1056) "REDIS_HOST")
+  (data (i32.const 1072) "API_TOKEN")
+
+  (type $sink (func (param i32) (result i32)))
+  (table 1 funcref)
+  (elem (i32.const 0) $id)
+  (func $id (param i32) (result i32) (local.get 0))
+
+  (func $noop)
+  (func $pair (result i32 i32) (i32.const 0) (i32.const 0))
+
+  (func (export "run")
+    ;; ptr flows into a block, which yields (ptr, len)
+    (i32.const 1024)
+    (block (param i32) (result i32 i32)
+      (i32.const 12))
+    (call $get (i32.const 4096))
+
+    ;; a call in between consumes nothing and must not disturb (ptr, len)
+    (i32.const 1040)
+    (i32.const 10)
+    (call $noop)
+    (call $get (i32.const 4096))
+
+    ;; both branches agree on (ptr, len)
+    (if (result i32 i32) (i32.const 1)
+      (then (i32.const 1056) (i32.const 10))
+      (else (i32.const 1056) (i32.const 10)))
+    (call $get (i32.const 4096))
+
+    ;; two unknown results, not one
+    (call $pair)
+    (call $get (i32.const 4096))
+
+    ;; an indirect call pops its argument and the table index, leaving
+    ;; (ptr, len) below them alone
+    (i32.const 1072)
+    (i32.const 9)
+    (drop (call_indirect (type $sink) (i32.const 7) (i32.const 0)))
+    (call $get (i32.const 4096))))
@@ -0,0 +1,27 @@
+;; A mutable global holding a name pointer, reassigned in some functions and
+;; branches but not others.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "REDIS_HOST_1")
+
+  (global $name (mut i32) (i32.const 1024))
+
+  ;; Reads the value it just stored
+  (func (export "reassign")
+    (global.set $name (i32.const 1040))
+    (call $get (global.get $name) (i32.const 12) (i32.const 4096)))
+
+  ;; Still sees the initial value, whichever function was walked before
+  (func (export "fresh")
+    (call $get (global.get $name) (i32.const 12) (i32.const 4096)))
+
+  ;; The else branch doesn't see the then branch's store, and after the if
+  ;; the global could hold either value
+  (func (export "branch") (param $flag i32)
+    (if (local.get $flag)
+      (then (global.set $name (i32.const 1040)))
+      (else (call $get (global.get $name) (i32.const 12) (i32.const 4096))))
+    (call $get (global.get $name) (i32.const 12) (i32.const 4096))))
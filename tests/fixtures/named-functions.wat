@@ -0,0 +1,9 @@
+;; Functions with symbol names, which `wat` writes to the `name` section.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "SECRET_KEY")
+
+  (func $myapp::config::load (export "load")
+    (call $get (i32.const 1024) (i32.const 10) (i32.const 4096))))
@@ -0,0 +1,16 @@
+;; A static table of name descriptors: an i32 pointer followed by the length
+;; as a u8 in one entry and a u16 in the other.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 512) "\00\04\00\00\0c")
+  (data (i32.const 520) "\10\04\00\00\0a\00")
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "REDIS_HOST")
+
+  (func (export "run")
+    (call $get (i32.load (i32.const 512)) (i32.load8_u offset=4 (i32.const 512))
+               (i32.const 4096))
+    (call $get (i32.load (i32.const 520)) (i32.load16_u offset=4 (i32.const 520))
+               (i32.const 4096))))
@@ -0,0 +1,18 @@
+;; A string table staged as passive data and copied into memory at startup.
+;; The lookup comes before the init function, so function order can't help.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data $strings "..API_TOKEN..SERVICE_URL")
+
+  (func (export "run")
+    (call $get (i32.const 2048) (i32.const 9) (i32.const 4096))
+    (call $get (i32.const 2057) (i32.const 11) (i32.const 4096)))
+
+  (func $init
+    (memory.init $strings (i32.const 2048) (i32.const 2) (i32.const 9))
+    (memory.init $strings (i32.const 2057) (i32.const 13) (i32.const 11))
+    (data.drop $strings))
+
+  (start $init))
@@ -0,0 +1,14 @@
+;; Position-independent module: data is placed at the loader-chosen
+;; `__memory_base` and addressed relative to it.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (import "env" "memory" (memory 1))
+  (import "env" "__memory_base" (global $memory_base i32))
+
+  (data (global.get $memory_base) "....DATABASE_URL")
+
+  (func (export "run")
+    (call $get
+      (i32.add (global.get $memory_base) (i32.const 4))
+      (i32.const 12)
+      (i32.const 4096))))
@@ -0,0 +1,16 @@
+;; A table of `(ptr, len)` entries indexed with memarg offsets: the name read
+;; is the second entry, so dropping the offset would report the first.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "LOG_LEVEL")
+  (data (i32.const 1040) "DATABASE_URL")
+  ;; [{ ptr: 1024, len: 9 }, { ptr: 1040, len: 12 }] at 2048, little-endian
+  (data (i32.const 2048) "\00\04\00\00\09\00\00\00\10\04\00\00\0c\00\00\00")
+
+  (func (export "read_config")
+    (call $get
+      (i32.load offset=8 (i32.const 2048))
+      (i32.load offset=12 (i32.const 2048))
+      (i32.const 4096))))
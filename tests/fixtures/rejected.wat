@@ -0,0 +1,14 @@
+;; Strings passed to an env import that aren't reported as names.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "RUST_BACKTRACE")
+  (data (i32.const 1056) "has space")
+
+  (func (export "run")
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096))
+    (call $get (i32.const 1040) (i32.const 14) (i32.const 4096))
+    (call $get (i32.const 1040) (i32.const 14) (i32.const 4096))
+    (call $get (i32.const 1056) (i32.const 9) (i32.const 4096))))
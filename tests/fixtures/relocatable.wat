@@ -0,0 +1,13 @@
+;; Shaped like a `.o` file from clang: the `linking` section marks it as
+;; relocatable, and the address passed to getenv is a placeholder (0) that a
+;; relocation would patch at link time.
+(module
+  (import "env" "getenv" (func $getenv (param i32) (result i32)))
+  (memory 1)
+
+  (data (i32.const 0) "DATABASE_URL\00LOG_LEVEL\00lowercase_word\00")
+
+  (func (export "run") (result i32)
+    (call $getenv (i32.const 0)))
+
+  (@custom "linking" "\02"))
@@ -0,0 +1,15 @@
+;; A helper whose calling convention takes the length before the pointer,
+;; next to a direct call in the usual (ptr, len) order.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "LOG_LEVEL")
+
+  (func $lookup (param $len i32) (param $ptr i32)
+    (call $get (local.get $ptr) (local.get $len) (i32.const 4096)))
+
+  (func (export "run")
+    (call $lookup (i32.const 12) (i32.const 1024))
+    (call $get (i32.const 1040) (i32.const 9) (i32.const 4096))))
@@ -0,0 +1,13 @@
+;; Overlapping data segments, where the later one wins, and a name whose
+;; inferred length runs past the end of its segment into uninitialized memory.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URX")
+  (data (i32.const 1035) "L")
+  (data (i32.const 2048) "LOG_LEVEL")
+
+  (func (export "run")
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096))
+    (call $get (i32.const 2048) (i32.const 16) (i32.const 4096))))
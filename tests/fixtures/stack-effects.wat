@@ -0,0 +1,41 @@
+;; Instructions whose only relevance is how many values they pop and push.
+;; Each leaves the stack misaligned for the following call if ignored.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+  (table 1 funcref)
+
+  (data (i32.const 1024) "CACHE_DIR")
+  (data (i32.const 1040) "QUEUE_NAME")
+  (data (i32.const 1056) "WORKER_COUNT")
+  (data (i32.const 1072) "REGION")
+
+  (func (export "memory_size")
+    i32.const 1024
+    i32.const 9
+    memory.size
+    drop
+    i32.const 4096
+    call $get)
+
+  (func (export "memory_fill")
+    i32.const 1040
+    i32.const 10
+    (memory.fill (i32.const 8192) (i32.const 0) (i32.const 16))
+    i32.const 4096
+    call $get)
+
+  (func (export "atomic_rmw")
+    i32.const 1056
+    i32.const 12
+    (drop (i32.atomic.rmw.add (i32.const 8192) (i32.const 1)))
+    i32.const 4096
+    call $get)
+
+  (func (export "table_size")
+    i32.const 1072
+    i32.const 6
+    table.size
+    drop
+    i32.const 4096
+    call $get))
@@ -0,0 +1,18 @@
+;; Names passed through tail calls, both into a wrapper and straight into the
+;; import.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "LOG_FORMAT")
+
+  ;; Only reaches the import via a tail call
+  (func $get_var (param i32 i32)
+    (return_call $get (local.get 0) (local.get 1) (i32.const 4096)))
+
+  (func (export "db")
+    (return_call $get_var (i32.const 1024) (i32.const 12)))
+
+  (func (export "log")
+    (return_call $get (i32.const 1040) (i32.const 10) (i32.const 4096))))
@@ -0,0 +1,18 @@
+;; Env-like (ptr, len) pairs passed to calls that never reach an env import:
+;; a logging import and a local helper. Only the config lookup counts.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (import "host" "log" (func $log (param i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "CACHE_DIR")
+  (data (i32.const 1056) "TEMP_PATH")
+
+  (func $hash (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1)))
+
+  (func (export "run")
+    (call $log (i32.const 1040) (i32.const 9))
+    (drop (call $hash (i32.const 1056) (i32.const 9)))
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096))))
@@ -0,0 +1,13 @@
+;; `read_named` passes a constant name to the config getter; `read_dynamic`
+;; passes one computed at runtime, which can't be recovered statically.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+
+  (func $read_named (export "read_named")
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096)))
+
+  (func $read_dynamic (export "read_dynamic") (param $ptr i32) (param $len i32)
+    (call $get (local.get $ptr) (local.get $len) (i32.const 4096))))
@@ -0,0 +1,15 @@
+;; One function uses relaxed SIMD, which walrus doesn't support; the rest of
+;; the module must still be scanned.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "DATABASE_URL")
+  (data (i32.const 1040) "LOST_VAR")
+
+  (func (export "read_config")
+    (call $get (i32.const 1024) (i32.const 12) (i32.const 4096)))
+
+  (func (export "relaxed") (param v128) (result v128)
+    (call $get (i32.const 1040) (i32.const 8) (i32.const 4096))
+    (i32x4.relaxed_trunc_f32x4_s (local.get 0))))
@@ -0,0 +1,37 @@
+;; Pointers and lengths routed through the width conversions LLVM inserts,
+;; e.g. a pointer widened to i64 for a helper and wrapped back at the call.
+(module
+  (import "wasi:config/store@0.2.0-draft" "get" (func $get (param i32 i32 i32)))
+  (memory 1)
+
+  (data (i32.const 1024) "SMTP_HOST")
+  (data (i32.const 1040) "SMTP_PORT")
+  (data (i32.const 1056) "MAIL_FROM")
+
+  (func (export "round_trip")
+    (i32.wrap_i64 (i64.extend_i32_u (i32.const 1024)))
+    (i32.wrap_i64 (i64.extend_i32_s (i32.const 9)))
+    i32.const 4096
+    call $get)
+
+  (func (export "sign_extend")
+    ;; 9 and 1040 are unchanged by sign-extending their low bits
+    (i32.extend8_s (i32.const 9))
+    drop
+    i32.const 1040
+    (i32.extend16_s (i32.const 9))
+    i32.const 4096
+    call $get)
+
+  (func (export "wide_length")
+    i32.const 1056
+    (i32.wrap_i64 (i64.extend32_s (i64.extend_i32_u (i32.const 9))))
+    i32.const 4096
+    call $get)
+
+  ;; A negative value sign-extended to i64 no longer fits, so no name
+  (func (export "negative")
+    (i32.wrap_i64 (i64.extend_i32_s (i32.const -4)))
+    i32.const 9
+    i32.const 4096
+    call $get))
@@ -1,4 +1,13 @@
-use wasm2env::{scan_wasm_bytes, scan_wasm_file};
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Stdio};
+
+use wasm2env::{
+    extract_module_memory, first_per_name, scan_against, scan_all_strings, scan_rejections,
+    scan_walrus_module, scan_wasm_bytes, scan_wasm_bytes_detailed,
+    scan_wasm_bytes_detailed_with_config, scan_wasm_bytes_with_config, scan_wasm_file,
+    scan_wasm_reader, scan_with_callback, scan_with_stats, write_json_stream, CalleeKind,
+    Confidence, HitSource, ScanConfig, ScanReport, Wasm2EnvError,
+};
 
 // ===== Existing real-world components =====
 
@@ -170,6 +179,39 @@ fn results_are_sorted() {
     assert_eq!(vars, sorted, "scan results should be sorted alphabetically");
 }
 
+#[test]
+fn detailed_results_are_deterministic() {
+    let bytes = std::fs::read("openai_component.wasm").unwrap();
+    let first = scan_wasm_bytes_detailed(&bytes).unwrap();
+    let second = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(first, second);
+
+    let keys: Vec<_> = first
+        .hits
+        .iter()
+        .map(|hit| (hit.module_index, hit.function_index, hit.ptr, &hit.name))
+        .collect();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(
+        keys, sorted,
+        "hits should be sorted by function, offset, name"
+    );
+}
+
+#[test]
+fn json_stream_of_several_files() {
+    let files = ["test-components/config-single.wasm", "missing.wasm"];
+    let mut out = Vec::new();
+    write_json_stream(&mut out, files).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(out.starts_with(
+        r#"[{"file":"test-components/config-single.wasm","env_vars":["API_KEY"]},{"file":"missing.wasm","error":"Failed to read WASM file: missing.wasm"#
+    ));
+    assert!(out.ends_with("\"}]\n"));
+}
+
 #[test]
 fn no_rust_runtime_noise() {
     let vars = scan_wasm_file("test-components/many-vars.wasm").unwrap();
@@ -204,6 +246,26 @@ fn nonexistent_file_returns_error() {
     assert!(scan_wasm_file("does_not_exist.wasm").is_err());
 }
 
+#[test]
+fn errors_can_be_matched() {
+    let err = scan_wasm_file("does_not_exist.wasm").unwrap_err();
+    assert!(
+        matches!(&err, Wasm2EnvError::Io { source, .. } if source.kind() == ErrorKind::NotFound),
+        "got: {err:?}"
+    );
+
+    let err = scan_wasm_bytes(b"not wasm").unwrap_err();
+    assert!(matches!(err, Wasm2EnvError::Parse(_)), "got: {err:?}");
+
+    let mut bytes = std::fs::read("test-components/multi-env.wasm").unwrap();
+    bytes.truncate(5000);
+    let err = scan_wasm_bytes(&bytes).unwrap_err();
+    assert!(
+        matches!(err, Wasm2EnvError::Truncated { offset: 5000 }),
+        "got: {err:?}"
+    );
+}
+
 // ===== Edge case tests =====
 
 // #11: Both std::env AND wasi:config/store in the same component
@@ -312,3 +374,942 @@ fn config_nested_calls() {
     let vars = scan_wasm_file("test-components/config-nested.wasm").unwrap();
     assert_eq!(vars, vec!["AUTH_TOKEN", "SERVICE_CONFIG"]);
 }
+
+// Detailed pass reports the same names, with call-site provenance
+#[test]
+fn detailed_hits_match_plain_scan() {
+    let bytes = std::fs::read("test-components/nested-calls.wasm").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+
+    assert_eq!(report.names(), scan_wasm_bytes(&bytes).unwrap());
+
+    for hit in &report.hits {
+        assert_eq!(hit.len as usize, hit.name.len());
+        assert!(
+            !hit.suspicious_overlap,
+            "unexpected overlap on {}",
+            hit.name
+        );
+    }
+}
+
+// Truncated input: best-effort hits from the modules that were complete
+#[test]
+fn truncated_component_is_best_effort() {
+    let mut bytes = std::fs::read("test-components/multi-env.wasm").unwrap();
+    // Drop the tail of the component-level sections; the core module is intact
+    bytes.truncate(bytes.len() - 1);
+
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert!(report.truncated_at.is_some());
+    assert_eq!(
+        report.names(),
+        vec!["API_KEY", "DATABASE_URL", "JWT_SECRET"]
+    );
+
+    let err = scan_wasm_bytes(&bytes).unwrap_err();
+    assert!(err.to_string().contains("truncated"), "got: {err}");
+
+    // Cutting into the core module itself yields no hits, but still no error
+    bytes.truncate(5000);
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.truncated_at, Some(5000));
+    assert!(report.hits.is_empty());
+}
+
+// Streaming from a reader matches scanning the whole file, for components,
+// core modules and truncated input
+#[test]
+fn scan_from_reader() {
+    for path in [
+        "openai_component.wasm",
+        "mcp_component.wasm",
+        "test-components/multi-env.wasm",
+        "test-components/config-many.wasm",
+    ] {
+        let file = std::fs::File::open(path).unwrap();
+        let from_reader = scan_wasm_reader(std::io::BufReader::new(file)).unwrap();
+        assert_eq!(from_reader, scan_wasm_file(path).unwrap(), "{path}");
+    }
+
+    let core_module = wat::parse_file("tests/fixtures/multi-value.wat").unwrap();
+    assert_eq!(
+        scan_wasm_reader(core_module.as_slice()).unwrap(),
+        scan_wasm_bytes(&core_module).unwrap()
+    );
+
+    let mut bytes = std::fs::read("test-components/multi-env.wasm").unwrap();
+    bytes.truncate(5000);
+    let err = scan_wasm_reader(bytes.as_slice()).unwrap_err();
+    assert!(err.to_string().contains("truncated"), "got: {err}");
+}
+
+// Emscripten side module (`dylink.0`) reading names through an imported C `getenv`
+#[test]
+fn emscripten_side_module_getenv() {
+    let bytes = wat::parse_file("tests/fixtures/emscripten-side-module.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(vars, vec!["DATABASE_URL", "LOG_FORMAT"]);
+}
+
+// String literals used as the fallback right after a read are reported as
+// the variable's default
+#[test]
+fn env_var_defaults() {
+    let bytes = wat::parse_file("tests/fixtures/env-defaults.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    let defaults: Vec<(&str, Option<&str>)> = report
+        .hits
+        .iter()
+        .map(|hit| (hit.name.as_str(), hit.default.as_deref()))
+        .collect();
+    assert_eq!(
+        defaults,
+        vec![
+            ("PORT", Some("8080")),
+            ("LOG_LEVEL", Some("info")),
+            ("API_KEY", None),
+            ("HOST", None),
+        ]
+    );
+}
+
+// Opt-in: a lone NUL-terminated pointer passed to a wrapper of a (ptr, len) API
+#[test]
+fn c_string_wrapper_opt_in() {
+    let bytes = wat::parse_file("tests/fixtures/c-string-wrapper.wat").unwrap();
+    assert!(scan_wasm_bytes(&bytes).unwrap().is_empty());
+
+    let config = ScanConfig::new().c_strings(true);
+    let vars = scan_wasm_bytes_with_config(&bytes, &config).unwrap();
+    assert_eq!(vars, vec!["SMTP_HOST", "SMTP_PORT"]);
+}
+
+// Opt-in export-name pass: low confidence, SCREAMING_SNAKE_CASE names only
+#[test]
+fn scan_exports_opt_in() {
+    let bytes = wat::parse_file("tests/fixtures/export-names.wat").unwrap();
+    assert!(scan_wasm_bytes(&bytes).unwrap().is_empty());
+
+    let config = ScanConfig::new().scan_exports(true);
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert_eq!(report.names(), vec!["FEATURE_FLAGS", "LEGACY_CONFIG_PATH"]);
+    for hit in &report.hits {
+        assert_eq!(hit.source, HitSource::ExportName);
+        assert_eq!(hit.confidence, Confidence::Low);
+        assert_eq!(hit.ptr, None);
+    }
+}
+
+// Lowercase names need the opt-in and a keyword as their last segment
+#[test]
+fn allow_lowercase_names() {
+    let bytes = wat::parse_file("tests/fixtures/lowercase-names.wat").unwrap();
+    let config = ScanConfig::new().scan_exports(true);
+    assert!(scan_wasm_bytes_with_config(&bytes, &config)
+        .unwrap()
+        .is_empty());
+
+    let config = config.allow_lowercase(true);
+    assert_eq!(
+        scan_wasm_bytes_with_config(&bytes, &config).unwrap(),
+        vec!["Redis_Host", "database_url", "my_api_token"]
+    );
+}
+
+// Each hit's file offset points at the bytes of its name in the scanned file
+#[test]
+fn file_offsets_point_at_names() {
+    let components = [
+        "test-components/multi-env.wasm",
+        "test-components/config-nested.wasm",
+    ];
+    let fixtures = ["passive-data.wat", "relocatable.wat", "env-defaults.wat"];
+    let binaries = components
+        .iter()
+        .map(|path| std::fs::read(path).unwrap())
+        .chain(
+            fixtures
+                .iter()
+                .map(|name| wat::parse_file(format!("tests/fixtures/{name}")).unwrap()),
+        );
+    for bytes in binaries {
+        let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+        assert!(!report.hits.is_empty());
+        for hit in &report.hits {
+            let offset = hit.file_offset.unwrap();
+            let len = hit.len as usize;
+            assert_eq!(&bytes[offset..offset + len], hit.name.as_bytes());
+        }
+    }
+
+    let bytes = wat::parse_file("tests/fixtures/export-names.wat").unwrap();
+    let config = ScanConfig::new().scan_exports(true);
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert!(report.hits.iter().all(|hit| hit.file_offset.is_none()));
+}
+
+// Ignore patterns drop matching names from every entry point
+#[test]
+fn ignore_patterns() {
+    let bytes = std::fs::read("test-components/multi-env.wasm").unwrap();
+    let config = ScanConfig::new().ignore(["jwt_*", "*_UR?"]);
+    assert_eq!(
+        scan_wasm_bytes_with_config(&bytes, &config).unwrap(),
+        vec!["API_KEY"]
+    );
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert!(report.stats.rejected_candidates.contains(&(
+        "DATABASE_URL".to_string(),
+        "matches an ignore pattern".to_string()
+    )));
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn ignore_regex() {
+    let bytes = std::fs::read("test-components/multi-env.wasm").unwrap();
+    let config = ScanConfig::new().ignore_regex(regex::Regex::new("SECRET|^API_").unwrap());
+    assert_eq!(
+        scan_wasm_bytes_with_config(&bytes, &config).unwrap(),
+        vec!["DATABASE_URL"]
+    );
+}
+
+// Streaming API delivers every hit (possibly repeated) as it's found
+#[test]
+fn callback_delivers_all_hits() {
+    let bytes = std::fs::read("test-components/multi-env.wasm").unwrap();
+
+    let mut names = Vec::new();
+    scan_with_callback(&bytes, &mut |hit| names.push(hit.name)).unwrap();
+    names.sort();
+    names.dedup();
+
+    assert_eq!(names, scan_wasm_bytes(&bytes).unwrap());
+}
+
+// The de-duplicating adapter passes each name through exactly once
+#[test]
+fn callback_first_per_name() {
+    let bytes = std::fs::read("test-components/multi-env.wasm").unwrap();
+
+    let mut names = Vec::new();
+    scan_with_callback(&bytes, &mut first_per_name(|hit| names.push(hit.name))).unwrap();
+    names.sort();
+
+    assert_eq!(names, scan_wasm_bytes(&bytes).unwrap());
+}
+
+// Extended-const offsets and initializers are folded before walrus sees them
+#[test]
+fn extended_const_offsets() {
+    let bytes = wat::parse_file("tests/fixtures/extended-const.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(vars, vec!["DATABASE_URL", "LOG_FORMAT"]);
+}
+
+// Each call-site hit names the import the variable is passed to
+#[test]
+fn hits_report_sink_import() {
+    let bytes = std::fs::read("test-components/config-single.wasm").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert!(!report.hits.is_empty());
+    for hit in &report.hits {
+        let sink = hit.sink.as_deref().unwrap();
+        assert!(sink.starts_with("wasi:config/store@"), "{sink}");
+        assert_eq!(sink.rsplit_once('.').map(|(_, name)| name), Some("get"));
+    }
+
+    let bytes = wat::parse_file("tests/fixtures/emscripten-side-module.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    for hit in &report.hits {
+        assert_eq!(hit.sink.as_deref(), Some("env.getenv"));
+    }
+}
+
+// ptr and len loaded from a static (ptr, len) struct via memarg offsets
+#[test]
+fn fat_pointer_loaded_from_data_segment() {
+    let bytes = wat::parse_file("tests/fixtures/fat-pointer-load.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL"]);
+    assert_eq!(report.hits[0].ptr, Some(1024));
+    assert_eq!(report.hits[0].len, 12);
+}
+
+// The memarg offset picks the entry, not just the base address
+#[test]
+fn load_offset_selects_entry() {
+    let bytes = wat::parse_file("tests/fixtures/pointer-table-offset.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL"]);
+    assert_eq!(report.hits[0].ptr, Some(1040));
+    assert_eq!(report.hits[0].len, 12);
+}
+
+// Data segments and constant globals are exposed as the scan sees them
+#[test]
+fn module_memory_contents() {
+    let bytes = wat::parse_file("tests/fixtures/fat-pointer-load.wat").unwrap();
+    let memories = extract_module_memory(&bytes).unwrap();
+    assert_eq!(memories.len(), 1);
+
+    let memory = &memories[0];
+    assert_eq!(memory.module_index, 0);
+    assert_eq!(memory.data_segments.len(), 2);
+    assert_eq!(memory.data_segments[0], (1024, b"DATABASE_URL".to_vec()));
+    assert_eq!(memory.globals.get(&0), Some(&2040));
+    assert_eq!(memory.read_string(1024, 12), Some("DATABASE_URL"));
+    assert_eq!(memory.read_bytes(2048, 4), Some(&[0, 4, 0, 0][..]));
+    assert_eq!(memory.read_string(1030, 12), None);
+
+    let bytes = std::fs::read("test-components/multi-env.wasm").unwrap();
+    let memories = extract_module_memory(&bytes).unwrap();
+    assert!(memories
+        .iter()
+        .any(|memory| !memory.data_segments.is_empty()));
+}
+
+// Spellings differing only in case collapse to the most upper-case one
+#[test]
+fn dedup_case_insensitive() {
+    let bytes = wat::parse_file("tests/fixtures/mixed-case-duplicates.wat").unwrap();
+    assert_eq!(
+        scan_wasm_bytes(&bytes).unwrap(),
+        vec!["DATABASE_URL", "Database_Url", "LOG_LEVEL"]
+    );
+
+    let config = ScanConfig::new().dedup_case_insensitive(true);
+    assert_eq!(
+        scan_wasm_bytes_with_config(&bytes, &config).unwrap(),
+        vec!["DATABASE_URL", "LOG_LEVEL"]
+    );
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert_eq!(report.hits.len(), 3);
+    assert_eq!(report.hits[1].name, "DATABASE_URL");
+    assert_eq!(report.hits[1].ptr, Some(1040));
+
+    // Hits without a pointer only differ by name, so renamed ones must be
+    // sorted and de-duplicated again
+    let bytes = wat::parse_file("tests/fixtures/export-case-duplicates.wat").unwrap();
+    let config = ScanConfig::new()
+        .scan_exports(true)
+        .allow_lowercase(true)
+        .dedup_case_insensitive(true);
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    let names: Vec<&str> = report.hits.iter().map(|hit| hit.name.as_str()).collect();
+    assert_eq!(names, ["DB_URL", "EXTRA_PATH"]);
+}
+
+// A region cleared with memory.fill no longer reads as its data segment
+#[test]
+fn memory_fill_clears_data() {
+    let bytes = wat::parse_file("tests/fixtures/memory-fill.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["LOG_LEVEL"]);
+    assert_eq!(report.stats.unresolved_env_reads.len(), 1);
+}
+
+// Dotted and dashed keys are opt-in, one separator at a time
+#[test]
+fn dotted_and_dashed_keys() {
+    let bytes = wat::parse_file("tests/fixtures/dotted-keys.wat").unwrap();
+    assert_eq!(scan_wasm_bytes(&bytes).unwrap(), vec!["LOG_LEVEL"]);
+
+    let config = ScanConfig::new().allow_dotted(true);
+    assert_eq!(
+        scan_wasm_bytes_with_config(&bytes, &config).unwrap(),
+        vec!["LOG_LEVEL", "app.database.url"]
+    );
+
+    let config = config.allow_dashed(true);
+    assert_eq!(
+        scan_wasm_bytes_with_config(&bytes, &config).unwrap(),
+        vec!["FEATURE-FLAG-X", "LOG_LEVEL", "app.database.url"]
+    );
+}
+
+// The counters cover every string read at a call site, kept or not
+#[test]
+fn scan_stats_counters() {
+    let bytes = wat::parse_file("tests/fixtures/dotted-keys.wat").unwrap();
+    let (hits, stats) = scan_with_stats(&bytes).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(stats.functions_analyzed, 1);
+    assert_eq!(stats.env_call_sites, 4);
+    assert_eq!(stats.strings_read, 4);
+    assert_eq!(stats.strings_rejected, 3);
+    assert_eq!(stats.rejected_candidates.len(), 3);
+
+    let bytes = std::fs::read("test-components/no-env.wasm").unwrap();
+    let (hits, stats) = scan_with_stats(&bytes).unwrap();
+    // The runtime's own lookups are read but all filtered out
+    assert!(hits.is_empty());
+    assert!(stats.functions_analyzed > 0);
+    assert_eq!(stats.strings_rejected, stats.strings_read);
+}
+
+// A global.set is visible only where it can have run
+#[test]
+fn mutable_global_stores() {
+    let bytes = wat::parse_file("tests/fixtures/mutable-globals.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    let hits: Vec<(Option<u32>, &str)> = report
+        .hits
+        .iter()
+        .map(|hit| (hit.function_index, hit.name.as_str()))
+        .collect();
+    assert_eq!(
+        hits,
+        vec![
+            (Some(1), "REDIS_HOST_1"),
+            (Some(2), "DATABASE_URL"),
+            (Some(3), "DATABASE_URL"),
+        ]
+    );
+    assert_eq!(report.stats.unresolved_env_reads, vec![(0, 3)]);
+}
+
+// A length loaded into a local is known at the call
+#[test]
+fn length_loaded_into_local() {
+    let bytes = wat::parse_file("tests/fixtures/loaded-length.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL", "LOG_LEVEL"]);
+    assert_eq!(report.hits[0].len, 12);
+    assert_eq!(report.hits[1].len, 9);
+    assert!(report.stats.unresolved_env_reads.is_empty());
+}
+
+// Functions using proposals walrus can't load are skipped, not the whole module
+#[test]
+fn unsupported_proposal_skips_function() {
+    let bytes = wat::parse_file("tests/fixtures/unsupported-proposal.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL"]);
+
+    let skipped = &report.stats.skipped_functions;
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].module_index, 0);
+    assert_eq!(skipped[0].function_index, 2);
+}
+
+// Locals of GC reference types don't cost the function its analysis
+#[test]
+fn gc_reference_type_locals() {
+    let bytes = wat::parse_file("tests/fixtures/gc-ref-local.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL"]);
+    assert!(report.stats.skipped_functions.is_empty());
+}
+
+// Only calls that reach an env import are inspected, not every (ptr, len)
+#[test]
+fn unrelated_calls_are_ignored() {
+    let bytes = wat::parse_file("tests/fixtures/unrelated-calls.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL"]);
+    assert_eq!(
+        report.hits[0].sink.as_deref(),
+        Some("wasi:config/store@0.2.0-draft.get")
+    );
+}
+
+// Text modules are assembled and scanned; the two kinds of parse error differ
+#[cfg(feature = "wat")]
+#[test]
+fn scan_wat_text() {
+    let vars = wasm2env::scan_wat_file("tests/fixtures/corroborated.wat").unwrap();
+    let bytes = wat::parse_file("tests/fixtures/corroborated.wat").unwrap();
+    assert_eq!(vars, scan_wasm_bytes(&bytes).unwrap());
+
+    let err = wasm2env::scan_wat_bytes(b"(module (func $f (i32.const)))").unwrap_err();
+    assert!(matches!(err, Wasm2EnvError::Wat(_)), "got: {err:?}");
+    assert_eq!(err.to_string(), "Failed to parse WAT");
+
+    let err = wasm2env::scan_wat_bytes(b"\0asm\x01\0\0\0\xff\x00").unwrap_err();
+    assert!(matches!(err, Wasm2EnvError::Parse(_)), "got: {err:?}");
+    assert!(!err.to_string().contains("WAT"), "got: {err}");
+
+    // Assembles, but to a binary with a bogus section
+    let err =
+        wasm2env::scan_wat_bytes(br#"(module binary "\00asm\01\00\00\00" "\ff\00")"#).unwrap_err();
+    assert!(matches!(err, Wasm2EnvError::Parse(_)), "got: {err:?}");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let bytes = wat::parse_file("tests/fixtures/env-defaults.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    let json = serde_json::to_string(&report).unwrap();
+    assert!(json.contains(r#""source":"call_site""#), "got: {json}");
+    assert!(json.contains(r#""confidence":"high""#), "got: {json}");
+    let reloaded: ScanReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(reloaded, report);
+
+    let config = ScanConfig::new()
+        .min_confidence(Confidence::Medium)
+        .scan_logs(true);
+    let json = serde_json::to_string(&config).unwrap();
+    assert!(json.contains(r#""min_confidence":"medium""#), "got: {json}");
+    let reloaded: ScanConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        scan_wasm_bytes_with_config(&bytes, &reloaded).unwrap(),
+        scan_wasm_bytes_with_config(&bytes, &config).unwrap()
+    );
+
+    // Missing fields take their defaults
+    let partial: ScanConfig = serde_json::from_str(r#"{"scan_exports":true}"#).unwrap();
+    assert_eq!(
+        format!("{partial:?}"),
+        format!("{:?}", ScanConfig::new().scan_exports(true))
+    );
+}
+
+// Memory, table and atomic instructions keep the stack height right
+#[test]
+fn stack_effects_keep_arguments_aligned() {
+    let bytes = wat::parse_file("tests/fixtures/stack-effects.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(
+        vars,
+        vec!["CACHE_DIR", "QUEUE_NAME", "REGION", "WORKER_COUNT"]
+    );
+}
+
+// Known pointers and lengths survive i32 <-> i64 and sign-extension round trips
+#[test]
+fn width_conversions_keep_values() {
+    let bytes = wat::parse_file("tests/fixtures/width-conversions.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(vars, vec!["MAIL_FROM", "SMTP_HOST", "SMTP_PORT"]);
+}
+
+// Imported globals come first in the global index space, and are unknown
+#[test]
+fn imported_globals_keep_indices() {
+    let bytes = wat::parse_file("tests/fixtures/imported-globals.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["QUEUE_URL"]);
+    assert_eq!(report.stats.unresolved_env_reads, vec![(0, 2)]);
+}
+
+// Passive segments copied in by `memory.init` are readable like active ones
+#[test]
+fn passive_data_segments() {
+    let bytes = wat::parse_file("tests/fixtures/passive-data.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(vars, vec!["API_TOKEN", "SERVICE_URL"]);
+}
+
+// memory64 modules pass i64 pointers and lengths
+#[test]
+fn memory64_pointers() {
+    let bytes = wat::parse_file("tests/fixtures/memory64.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(vars, vec!["DATABASE_URL", "LOG_LEVEL", "REDIS_HOST"]);
+}
+
+// Pointers and lengths computed with mul, shifts and bitwise ops are folded
+#[test]
+fn computed_offsets() {
+    let bytes = wat::parse_file("tests/fixtures/computed-offsets.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(
+        vars,
+        vec![
+            "API_TOKEN",
+            "CACHE_DIR",
+            "DATABASE_URL",
+            "LOG_LEVEL",
+            "REDIS_HOST"
+        ]
+    );
+}
+
+// Byte and halfword loads from data segments resolve like i32 ones
+#[test]
+fn narrow_loads_from_data() {
+    let bytes = wat::parse_file("tests/fixtures/narrow-loads.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(vars, vec!["DATABASE_URL", "REDIS_HOST"]);
+}
+
+// Every (ptr, len) string passed to any call, not just env lookups
+#[test]
+fn all_call_strings() {
+    let bytes = wat::parse_file("tests/fixtures/unrelated-calls.wat").unwrap();
+    let strings = scan_all_strings(&bytes).unwrap();
+    assert_eq!(strings, vec!["CACHE_DIR", "DATABASE_URL", "TEMP_PATH"]);
+}
+
+// Later segments win where they overlap; with partial_strings, a name that
+// runs into uninitialized memory is read up to the gap
+#[test]
+fn data_segment_edges() {
+    let bytes = wat::parse_file("tests/fixtures/segment-edges.wat").unwrap();
+    assert_eq!(scan_wasm_bytes(&bytes).unwrap(), vec!["DATABASE_URL"]);
+
+    let config = ScanConfig::new().partial_strings(true);
+    let vars = scan_wasm_bytes_with_config(&bytes, &config).unwrap();
+    assert_eq!(vars, vec!["DATABASE_URL", "LOG_LEVEL"]);
+}
+
+// Hits name the function from the `name` section, when there is one
+#[test]
+fn function_names_from_name_section() {
+    let bytes = wat::parse_file("tests/fixtures/named-functions.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["SECRET_KEY"]);
+    assert_eq!(
+        report.hits[0].function_name.as_deref(),
+        Some("myapp::config::load")
+    );
+
+    // No symbol for this function: only the index is known
+    let bytes = wat::parse_file("tests/fixtures/unrelated-calls.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.hits[0].function_name, None);
+    assert!(report.hits[0].function_index.is_some());
+}
+
+// Block/call arities from the type section keep the stack model in sync
+#[test]
+fn multi_value_blocks_and_calls() {
+    let bytes = wat::parse_file("tests/fixtures/multi-value.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(
+        vars,
+        vec!["API_TOKEN", "DATABASE_URL", "LOG_FORMAT", "REDIS_HOST"]
+    );
+}
+
+// Lengths pushed before the pointer are read the right way round
+#[test]
+fn length_before_pointer() {
+    let bytes = wat::parse_file("tests/fixtures/reversed-pair.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL", "LOG_LEVEL"]);
+
+    let reversed = report.hits.iter().find(|hit| hit.name == "DATABASE_URL");
+    assert_eq!(
+        reversed.map(|hit| (hit.ptr, hit.len)),
+        Some((Some(1024), 12))
+    );
+    let forward = report.hits.iter().find(|hit| hit.name == "LOG_LEVEL");
+    assert_eq!(forward.map(|hit| (hit.ptr, hit.len)), Some((Some(1040), 9)));
+    assert!(report.stats.rejected_candidates.is_empty());
+}
+
+// return_call arguments are inspected like a regular call's
+#[test]
+fn tail_called_getenv() {
+    let bytes = wat::parse_file("tests/fixtures/tail-call.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(vars, vec!["DATABASE_URL", "LOG_FORMAT"]);
+}
+
+// Bare offsets into a NUL-separated blob are only read when opted in
+#[test]
+fn interned_strings_blob() {
+    let bytes = wat::parse_file("tests/fixtures/interned-strings.wat").unwrap();
+    assert!(scan_wasm_bytes(&bytes).unwrap().is_empty());
+
+    let config = ScanConfig::new().interned_strings(true);
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL", "LOG_LEVEL"]);
+    assert_eq!(report.hits[1].len, 9);
+}
+
+// Names in error messages written with fd_write, when asked for
+#[test]
+fn fd_write_log_messages() {
+    let bytes = wat::parse_file("tests/fixtures/fd-write-log.wat").unwrap();
+    assert!(scan_wasm_bytes(&bytes).unwrap().is_empty());
+
+    let config = ScanConfig::new().scan_logs(true);
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL"]);
+    let hit = &report.hits[0];
+    assert_eq!(hit.source, HitSource::LogMessage);
+    assert_eq!(hit.ptr, Some(1031));
+    assert_eq!(hit.sink.as_deref(), Some("wasi_snapshot_preview1.fd_write"));
+}
+
+// Identifiers in DWARF debug info, when asked for
+#[test]
+fn debug_str_identifiers() {
+    let bytes = wat::parse_file("tests/fixtures/debug-str.wat").unwrap();
+    assert!(scan_wasm_bytes(&bytes).unwrap().is_empty());
+
+    let config = ScanConfig::new().debug_info(true);
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL"]);
+    assert!(report
+        .hits
+        .iter()
+        .all(|hit| hit.source == HitSource::DebugInfo && hit.confidence == Confidence::Low));
+}
+
+// Imports of unknown host ABIs are traced when a classifier names them
+#[test]
+fn custom_callee_classifier() {
+    let bytes = wat::parse_file("tests/fixtures/custom-host.wat").unwrap();
+    assert!(scan_wasm_bytes(&bytes).unwrap().is_empty());
+
+    let config = ScanConfig::new().callee_classifier(|module, name| match (module, name) {
+        ("my-host", "config-get") => CalleeKind::StringSink,
+        ("my-host", "getenv") => CalleeKind::Getenv,
+        _ => CalleeKind::Ignore,
+    });
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL", "LOG_LEVEL"]);
+    assert_eq!(report.hits[0].sink.as_deref(), Some("my-host.config-get"));
+}
+
+// A name found at a call site and in a log message is corroborated
+#[test]
+fn corroborated_across_methods() {
+    let bytes = wat::parse_file("tests/fixtures/corroborated.wat").unwrap();
+    let config = ScanConfig::new().scan_logs(true);
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL", "LOG_LEVEL"]);
+    for hit in &report.hits {
+        let expected = hit.name == "DATABASE_URL";
+        assert_eq!(hit.corroborated, expected, "{hit:?}");
+        assert_eq!(hit.confidence == Confidence::High, expected, "{hit:?}");
+    }
+
+    let config = config.corroborated_only(true);
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL"]);
+}
+
+// Hits below the configured confidence are dropped; corroboration counts
+#[test]
+fn min_confidence_filter() {
+    let bytes = wat::parse_file("tests/fixtures/corroborated.wat").unwrap();
+    let config = ScanConfig::new()
+        .scan_logs(true)
+        .min_confidence(Confidence::Medium);
+    let report = scan_wasm_bytes_detailed_with_config(&bytes, &config).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL"]);
+    assert!(report
+        .hits
+        .iter()
+        .all(|hit| hit.confidence >= Confidence::Medium));
+
+    let bytes = wat::parse_file("tests/fixtures/export-names.wat").unwrap();
+    let config = ScanConfig::new()
+        .scan_exports(true)
+        .min_confidence(Confidence::High);
+    assert!(scan_wasm_bytes_with_config(&bytes, &config)
+        .unwrap()
+        .is_empty());
+}
+
+// Reading the whole environment is flagged even with no names to report
+#[test]
+fn whole_environment_enumeration() {
+    let bytes = wat::parse_file("tests/fixtures/environ-enumeration.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert!(report.hits.is_empty());
+    assert_eq!(report.stats.whole_environment_modules, vec![0]);
+
+    let bytes = wat::parse_file("tests/fixtures/unresolved-read.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert!(report.stats.whole_environment_modules.is_empty());
+}
+
+/// Run the CLI on `wasm_bytes` piped to stdin, returning its stdout.
+fn run_cli(args: &[&str], wasm_bytes: &[u8]) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wasm2env"))
+        .args(args)
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(wasm_bytes).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// The CLI doesn't call a module that enumerates the environment dependency-free
+#[test]
+fn whole_environment_enumeration_output() {
+    let bytes = wat::parse_file("tests/fixtures/environ-enumeration.wat").unwrap();
+
+    let out = run_cli(&[], &bytes);
+    assert!(out.contains("No environment variable names detected, but the module reads"));
+    assert!(!out.contains("No environment variable dependencies detected."));
+    assert!(out.contains("Overall: UNKNOWN (reads the whole environment, names unknown)"));
+
+    let out = run_cli(&["--format", "json"], &bytes);
+    assert_eq!(out, "[{\"name\":null,\"whole_environment\":true}]\n");
+}
+
+// A component-level environment import is reported without any core module
+// using it
+#[test]
+fn component_environment_import() {
+    let bytes = wat::parse_file("tests/fixtures/component-environment-import.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert!(report.hits.is_empty());
+    assert!(report.stats.whole_environment_modules.is_empty());
+    assert_eq!(
+        report.stats.environment_imports,
+        vec!["wasi:cli/environment@0.2.0"]
+    );
+
+    let bytes = std::fs::read("test-components/single-env.wasm").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(
+        report.stats.environment_imports,
+        vec!["wasi:cli/environment@0.2.6"]
+    );
+}
+
+// Object files fall back to reporting env-like data strings
+#[test]
+fn relocatable_object_file() {
+    let bytes = wat::parse_file("tests/fixtures/relocatable.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL", "LOG_LEVEL"]);
+    assert_eq!(report.stats.relocatable_modules, vec![0]);
+    assert!(report
+        .hits
+        .iter()
+        .all(|hit| hit.source == HitSource::DataString && hit.confidence == Confidence::Low));
+}
+
+// Addresses relative to an imported __memory_base still resolve
+#[test]
+fn position_independent_module() {
+    let bytes = wat::parse_file("tests/fixtures/pic.wat").unwrap();
+    let vars = scan_wasm_bytes(&bytes).unwrap();
+    assert_eq!(vars, vec!["DATABASE_URL"]);
+}
+
+// Strings read at env call sites but filtered out are listed with a reason
+#[test]
+fn rejected_candidates_have_reasons() {
+    let bytes = wat::parse_file("tests/fixtures/rejected.wat").unwrap();
+    let rejections = scan_rejections(&bytes).unwrap();
+    assert_eq!(
+        rejections,
+        vec![
+            (
+                "RUST_BACKTRACE".to_string(),
+                "in the noise list".to_string()
+            ),
+            (
+                "has space".to_string(),
+                "contains characters other than letters, digits and '_'".to_string()
+            ),
+        ]
+    );
+}
+
+// Names read from a single function can be filtered out
+#[test]
+fn min_functions_threshold() {
+    let bytes = wat::parse_file("tests/fixtures/min-functions.wat").unwrap();
+    assert_eq!(
+        scan_wasm_bytes(&bytes).unwrap(),
+        vec!["DATABASE_URL", "LOG_LEVEL"]
+    );
+
+    let config = ScanConfig::new().min_functions(2);
+    let vars = scan_wasm_bytes_with_config(&bytes, &config).unwrap();
+    assert_eq!(vars, vec!["DATABASE_URL"]);
+}
+
+// Detected names checked against a declared list
+#[test]
+fn scan_against_declared_vars() {
+    let bytes = std::fs::read("test-components/config-and-env.wasm").unwrap();
+    let expected: Vec<String> = ["RUNTIME_MODE", "CONFIG_DB_URL", "LOG_LEVEL", "RUNTIME_MODE"]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let diff = scan_against(&bytes, &expected).unwrap();
+
+    assert_eq!(diff.satisfied, vec!["CONFIG_DB_URL", "RUNTIME_MODE"]);
+    assert_eq!(diff.missing_from_module, vec!["LOG_LEVEL"]);
+    assert_eq!(diff.missing_from_schema, vec!["CONFIG_API_KEY"]);
+    assert!(!diff.is_exact_match());
+
+    let exact = scan_against(&bytes, &scan_wasm_bytes(&bytes).unwrap()).unwrap();
+    assert!(exact.is_exact_match());
+}
+
+// Env reads whose name couldn't be reconstructed are reported, not dropped
+#[test]
+fn unresolved_env_reads_reported() {
+    let bytes = wat::parse_file("tests/fixtures/unresolved-read.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL"]);
+    assert_eq!(report.stats.unresolved_env_reads, vec![(0, 2)]);
+
+    let bytes = std::fs::read("test-components/no-env.wasm").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert!(report.stats.unresolved_env_reads.is_empty());
+}
+
+// try/catch/delegate/throw/rethrow are lowered so protected bodies are analyzed
+#[test]
+fn exception_handling_module() {
+    let bytes = wat::parse_file("tests/fixtures/exceptions.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(
+        report.names(),
+        vec!["DATABASE_URL", "LOG_FORMAT", "REDIS_HOST"]
+    );
+    assert!(report.stats.skipped_functions.is_empty());
+
+    // catch_all, delegate and rethrow are lowered without any tag
+    let bytes = wat::parse_file("tests/fixtures/exceptions-without-tags.wat").unwrap();
+    let report = scan_wasm_bytes_detailed(&bytes).unwrap();
+    assert_eq!(report.names(), vec!["DATABASE_URL", "LOG_FORMAT"]);
+    assert!(report.stats.skipped_functions.is_empty());
+}
+
+// normalized() makes reports from different APIs directly comparable
+#[test]
+fn normalized_reports_compare_equal() {
+    let bytes = std::fs::read("test-components/nested-calls.wasm").unwrap();
+
+    let mut streamed = ScanReport::default();
+    scan_with_callback(&bytes, &mut |hit| streamed.hits.push(hit)).unwrap();
+    let detailed = scan_wasm_bytes_detailed(&bytes).unwrap();
+
+    // The callback API only delivers hits, not stats
+    assert_eq!(
+        streamed.normalized().hits,
+        detailed.clone().normalized().hits
+    );
+    assert_eq!(
+        detailed.clone().normalized(),
+        detailed.normalized().normalized()
+    );
+}
+
+// Embedders that already hold a walrus module can run just the detection core
+#[test]
+fn scan_prepared_walrus_module() {
+    let bytes = wat::parse_file("tests/fixtures/multi-value.wat").unwrap();
+    let module = walrus::Module::from_buffer(&bytes).unwrap();
+
+    let report = scan_walrus_module(&module, &ScanConfig::default());
+    // Only a scanned binary has file offsets
+    let mut expected = scan_wasm_bytes_detailed(&bytes).unwrap();
+    for hit in &mut expected.hits {
+        hit.file_offset = None;
+    }
+    assert_eq!(report, expected);
+}